@@ -0,0 +1,222 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_app::grid_plugin::{
+    cast_ray, chunk_of, ChunkMeshBuilder, HexFace, HexHit, Tile, TileEvent,
+};
+use bevy_app::{ready_app, GridConfig, GridTracker, HeadlessPlugins, MapFile, TileVariant};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use hexx::{shapes, Hex, HexLayout, HexOrientation};
+use rand::prelude::*;
+
+/// Board radius and column height giving roughly 10k, 100k and 1M tiles.
+const MAP_SIZES: [(u32, u32); 3] = [(40, 2), (57, 10), (57, 100)];
+
+fn layout() -> HexLayout {
+    HexLayout {
+        orientation: HexOrientation::Pointy,
+        ..default()
+    }
+}
+
+/// A hexagonal board of `radius` whose columns have random heights averaging `height`.
+fn columns(radius: u32, height: u32) -> HashMap<Hex, Vec<Tile>> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let variants = [TileVariant::Cyan, TileVariant::Purple, TileVariant::Orange];
+    shapes::hexagon(Hex::ZERO, radius)
+        .map(|hex| {
+            let column = (0..rng.gen_range(0..=2 * height))
+                .map(|_| Tile {
+                    entity: Entity::PLACEHOLDER,
+                    variant: variants.choose(&mut rng).unwrap().clone(),
+                })
+                .collect();
+            (hex, column)
+        })
+        .collect()
+}
+
+fn tile_count(columns: &HashMap<Hex, Vec<Tile>>) -> u64 {
+    columns.values().map(|column| column.len() as u64).sum()
+}
+
+fn placement(c: &mut Criterion) {
+    const TILES: usize = 1000;
+    let mut group = c.benchmark_group("placement");
+    group.throughput(Throughput::Elements(TILES as u64));
+    group.sample_size(20);
+    group.bench_function("place_1000_tiles", |b| {
+        b.iter_batched(
+            || {
+                let app = ready_app(HeadlessPlugins);
+                let board: Vec<Hex> = app
+                    .world
+                    .resource::<GridTracker>()
+                    .columns
+                    .keys()
+                    .copied()
+                    .collect();
+                let mut rng = StdRng::seed_from_u64(0);
+                let hexes: Vec<Hex> = (0..TILES)
+                    .map(|_| *board.choose(&mut rng).unwrap())
+                    .collect();
+                (app, hexes)
+            },
+            |(mut app, hexes)| {
+                for hex in hexes {
+                    let hit = HexHit {
+                        hex,
+                        layer: 0,
+                        face: HexFace::Top,
+                        position: Vec3::ZERO,
+                    };
+                    // Released right away, so every tile is a click rather than part of a drag.
+                    app.world.send_event(TileEvent::Down(hit));
+                    app.world.send_event(TileEvent::Up(hit));
+                }
+                app.update();
+                app
+            },
+            BatchSize::PerIteration,
+        );
+    });
+    group.finish();
+}
+
+fn chunk_meshes(c: &mut Criterion) {
+    let layout = layout();
+    let config = GridConfig::default();
+    let mut group = c.benchmark_group("chunk_meshes");
+    group.sample_size(10);
+    for (radius, height) in MAP_SIZES {
+        let columns = columns(radius, height);
+        let chunks: Vec<IVec2> = columns
+            .keys()
+            .map(|hex| chunk_of(*hex, config.chunk_size))
+            .collect::<bevy::utils::HashSet<_>>()
+            .into_iter()
+            .collect();
+        let tiles = tile_count(&columns);
+        let builder = ChunkMeshBuilder {
+            layout: &layout,
+            columns: &columns,
+            layer_height: config.layer_height,
+            margin: config.margin,
+        };
+        group.throughput(Throughput::Elements(tiles));
+        group.bench_with_input(
+            BenchmarkId::new("whole_map", tiles),
+            &chunks,
+            |b, chunks| {
+                b.iter(|| {
+                    chunks
+                        .iter()
+                        .map(|chunk| builder.build(*chunk, config.chunk_size).len())
+                        .sum::<usize>()
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("one_chunk", tiles),
+            &IVec2::ZERO,
+            |b, chunk| {
+                b.iter(|| builder.build(*chunk, config.chunk_size));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn neighbor_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neighbor_queries");
+    for (radius, height) in MAP_SIZES {
+        let columns = columns(radius, height);
+        let hexes: Vec<Hex> = columns.keys().copied().collect();
+        group.throughput(Throughput::Elements(hexes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("neighbor_heights", tile_count(&columns)),
+            &hexes,
+            |b, hexes| {
+                b.iter(|| {
+                    hexes
+                        .iter()
+                        .flat_map(|hex| hex.all_neighbors())
+                        .filter_map(|neighbor| columns.get(&neighbor))
+                        .map(Vec::len)
+                        .sum::<usize>()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn map_files(c: &mut Criterion) {
+    let mut group = c.benchmark_group("map_files");
+    group.sample_size(10);
+    for (radius, height) in MAP_SIZES {
+        let columns = columns(radius, height);
+        let tiles = tile_count(&columns);
+        let tracker = GridTracker {
+            columns,
+            ..default()
+        };
+        let source = MapFile::from_tracker(&tracker).to_ron().unwrap();
+        group.throughput(Throughput::Elements(tiles));
+        group.bench_with_input(BenchmarkId::new("save", tiles), &tracker, |b, tracker| {
+            b.iter(|| MapFile::from_tracker(tracker).to_ron().unwrap().len());
+        });
+        group.bench_with_input(BenchmarkId::new("load", tiles), &source, |b, source| {
+            b.iter(|| MapFile::from_ron(source).unwrap().columns.len());
+        });
+    }
+    group.finish();
+}
+
+fn ray_casts(c: &mut Criterion) {
+    const RAYS: usize = 100;
+    let layout = layout();
+    let config = GridConfig::default();
+    let mut group = c.benchmark_group("ray_casts");
+    group.throughput(Throughput::Elements(RAYS as u64));
+    for (radius, height) in MAP_SIZES {
+        let columns = columns(radius, height);
+        let mut rng = StdRng::seed_from_u64(0);
+        let extent = radius as f32;
+        let rays: Vec<Ray> = (0..RAYS)
+            .map(|_| {
+                let target = Vec3::new(
+                    rng.gen_range(-extent..extent),
+                    0.0,
+                    rng.gen_range(-extent..extent),
+                );
+                let origin = target + Vec3::new(0.0, 10.0, 12.0) * extent / 4.0;
+                Ray {
+                    origin,
+                    direction: (target - origin).normalize(),
+                }
+            })
+            .collect();
+        group.bench_with_input(
+            BenchmarkId::new("cast_ray", tile_count(&columns)),
+            &rays,
+            |b, rays| {
+                b.iter(|| {
+                    rays.iter()
+                        .filter_map(|ray| cast_ray(*ray, &layout, &columns, config.layer_height))
+                        .count()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    placement,
+    chunk_meshes,
+    neighbor_queries,
+    map_files,
+    ray_casts
+);
+criterion_main!(benches);
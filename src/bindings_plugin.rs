@@ -0,0 +1,608 @@
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::input::gamepad::GamepadButtonType;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::grid_plugin::Tool;
+
+/// Something the user can do, bound to one or more inputs in `InputBindings`. Systems read the
+/// resolved state from `Res<Input<Action>>` instead of checking keys or buttons directly.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    OrbitLeft,
+    OrbitRight,
+    OrbitUp,
+    OrbitDown,
+    /// Held while dragging the mouse to orbit.
+    Orbit,
+    /// Held while dragging the mouse to pan.
+    Pan,
+    /// Double-clicked on a tile to center the camera on it.
+    CenterOnTile,
+    ToggleProjection,
+    /// Held while scrolling to change the field of view instead of zooming.
+    AdjustFov,
+    SnapYaw,
+    /// Recall camera bookmark `1..=9`.
+    Bookmark(u8),
+    StoreBookmark(u8),
+    FrameAll,
+    FrameSelection,
+    Place,
+    Erase,
+    Undo,
+    /// Switch the tool used by pointer presses and drags.
+    UseTool(Tool),
+    /// Move the hex cursor towards the right of the screen, then counter-clockwise in 60° steps.
+    MoveCursor(u8),
+    NextVariant,
+    PreviousVariant,
+    /// Select palette entry `1..=9`.
+    SelectVariant(u8),
+    SaveMap,
+    LoadMap,
+    /// Pause editing, or go back from a menu.
+    Pause,
+    ShowBindings,
+    ToggleDebugInfo,
+    ToggleInspector,
+}
+
+impl Action {
+    /// Every action with the built-in tools, in the order they are listed in the bindings panel.
+    pub fn all() -> Vec<Action> {
+        Action::with_tools(Tool::BUILT_IN)
+    }
+
+    /// Every action, with a `UseTool` for each of `tools`, e.g. those of `EditorTools`.
+    pub fn with_tools(tools: impl IntoIterator<Item = Tool>) -> Vec<Action> {
+        use Action::*;
+        let mut actions = vec![Place, Erase, Undo];
+        actions.extend(tools.into_iter().map(UseTool));
+        actions.extend([NextVariant, PreviousVariant]);
+        actions.extend((1..=9).map(SelectVariant));
+        actions.extend((0..6).map(MoveCursor));
+        actions.extend([
+            OrbitLeft,
+            OrbitRight,
+            OrbitUp,
+            OrbitDown,
+            Orbit,
+            Pan,
+            CenterOnTile,
+            ToggleProjection,
+            AdjustFov,
+            SnapYaw,
+            FrameAll,
+            FrameSelection,
+        ]);
+        actions.extend((1..=9).map(Bookmark));
+        actions.extend((1..=9).map(StoreBookmark));
+        actions.extend([
+            SaveMap,
+            LoadMap,
+            Pause,
+            ShowBindings,
+            ToggleDebugInfo,
+            ToggleInspector,
+        ]);
+        actions
+    }
+
+    /// Actions triggered by a double-click may share their input with single-click actions.
+    fn is_double_click(self) -> bool {
+        self == Action::CenterOnTile
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CURSOR: [&str; 6] = [
+            "right",
+            "up right",
+            "up left",
+            "left",
+            "down left",
+            "down right",
+        ];
+        match self {
+            Action::Bookmark(number) => write!(f, "Bookmark {number}"),
+            Action::StoreBookmark(number) => write!(f, "Store bookmark {number}"),
+            Action::SelectVariant(number) => write!(f, "Select variant {number}"),
+            Action::UseTool(tool) => write!(f, "{tool} tool"),
+            Action::MoveCursor(direction) => {
+                write!(f, "Cursor {}", CURSOR[*direction as usize % 6])
+            }
+            action => write!(f, "{action:?}"),
+        }
+    }
+}
+
+/// A key, mouse button or gamepad button. Gamepad buttons match on any connected gamepad.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl InputButton {
+    /// Whether both are keys, both mouse buttons or both gamepad buttons.
+    pub fn same_device(&self, other: &InputButton) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// Either side of a modifier key.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    Shift,
+    Control,
+    Alt,
+}
+
+impl Modifier {
+    const ALL: [Modifier; 3] = [Modifier::Shift, Modifier::Control, Modifier::Alt];
+
+    fn keys(self) -> [KeyCode; 2] {
+        match self {
+            Modifier::Shift => [KeyCode::LShift, KeyCode::RShift],
+            Modifier::Control => [KeyCode::LControl, KeyCode::RControl],
+            Modifier::Alt => [KeyCode::LAlt, KeyCode::RAlt],
+        }
+    }
+
+    fn of(key: KeyCode) -> Option<Modifier> {
+        Self::ALL
+            .into_iter()
+            .find(|modifier| modifier.keys().contains(&key))
+    }
+}
+
+/// An input, optionally combined with a modifier. The modifier has to match exactly: a binding
+/// without one does not fire while a modifier is held, so `Z` and `Ctrl+Z` never both trigger.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub input: InputButton,
+    #[serde(default)]
+    pub modifier: Option<Modifier>,
+}
+
+impl Binding {
+    pub fn new(input: InputButton) -> Self {
+        Self {
+            input,
+            modifier: None,
+        }
+    }
+
+    pub fn with(modifier: Modifier, input: InputButton) -> Self {
+        Self {
+            input,
+            modifier: Some(modifier),
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(modifier) = self.modifier {
+            write!(f, "{modifier:?}+")?;
+        }
+        match self.input {
+            InputButton::Key(key) => write!(f, "{key:?}"),
+            InputButton::Mouse(button) => write!(f, "Mouse {button:?}"),
+            InputButton::Gamepad(button) => write!(f, "Gamepad {button:?}"),
+        }
+    }
+}
+
+/// Two actions sharing a binding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub binding: Binding,
+    pub actions: (Action, Action),
+}
+
+/// Which inputs trigger each action, as stored in the settings file.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InputBindings {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Action::*;
+        use InputButton::{Gamepad, Key, Mouse};
+        let key = |key| vec![Binding::new(Key(key))];
+        let digits = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+        let function_keys = [
+            KeyCode::F1,
+            KeyCode::F2,
+            KeyCode::F3,
+            KeyCode::F4,
+            KeyCode::F5,
+            KeyCode::F6,
+            KeyCode::F7,
+            KeyCode::F8,
+            KeyCode::F9,
+        ];
+        let cursor_keys = [
+            KeyCode::D,
+            KeyCode::E,
+            KeyCode::Q,
+            KeyCode::A,
+            KeyCode::Z,
+            KeyCode::C,
+        ];
+
+        let mut bindings = HashMap::from_iter([
+            (OrbitLeft, key(KeyCode::Left)),
+            (OrbitRight, key(KeyCode::Right)),
+            (OrbitUp, key(KeyCode::Up)),
+            (OrbitDown, key(KeyCode::Down)),
+            (Orbit, vec![Binding::new(Mouse(MouseButton::Right))]),
+            (
+                Pan,
+                vec![
+                    Binding::new(Mouse(MouseButton::Middle)),
+                    Binding::with(Modifier::Shift, Mouse(MouseButton::Right)),
+                ],
+            ),
+            (CenterOnTile, vec![Binding::new(Mouse(MouseButton::Right))]),
+            (ToggleProjection, key(KeyCode::P)),
+            (AdjustFov, key(KeyCode::LControl)),
+            (SnapYaw, key(KeyCode::Home)),
+            (FrameAll, key(KeyCode::F)),
+            (FrameSelection, key(KeyCode::Period)),
+            (
+                Place,
+                vec![
+                    Binding::new(Mouse(MouseButton::Left)),
+                    Binding::new(Key(KeyCode::Space)),
+                    Binding::new(Gamepad(GamepadButtonType::South)),
+                ],
+            ),
+            (
+                Erase,
+                vec![
+                    Binding::new(Key(KeyCode::X)),
+                    Binding::new(Gamepad(GamepadButtonType::East)),
+                ],
+            ),
+            (
+                Undo,
+                vec![
+                    Binding::with(Modifier::Control, Key(KeyCode::Z)),
+                    Binding::new(Gamepad(GamepadButtonType::West)),
+                ],
+            ),
+            (
+                NextVariant,
+                vec![
+                    Binding::new(Key(KeyCode::Tab)),
+                    Binding::new(Gamepad(GamepadButtonType::RightTrigger)),
+                ],
+            ),
+            (
+                PreviousVariant,
+                vec![
+                    Binding::new(Key(KeyCode::Grave)),
+                    Binding::new(Gamepad(GamepadButtonType::LeftTrigger)),
+                ],
+            ),
+            (
+                SaveMap,
+                vec![Binding::with(Modifier::Control, Key(KeyCode::S))],
+            ),
+            (
+                LoadMap,
+                vec![Binding::with(Modifier::Control, Key(KeyCode::O))],
+            ),
+            (UseTool(Tool::Build), key(KeyCode::B)),
+            (UseTool(Tool::Erase), key(KeyCode::R)),
+            (UseTool(Tool::Paint), key(KeyCode::T)),
+            (UseTool(Tool::Fill), key(KeyCode::G)),
+            (UseTool(Tool::Select), key(KeyCode::S)),
+            (UseTool(Tool::Eyedropper), key(KeyCode::I)),
+            (
+                Pause,
+                vec![
+                    Binding::new(Key(KeyCode::Escape)),
+                    Binding::new(Gamepad(GamepadButtonType::Start)),
+                ],
+            ),
+            (ShowBindings, key(KeyCode::F10)),
+            (ToggleDebugInfo, key(KeyCode::F11)),
+            (ToggleInspector, key(KeyCode::F12)),
+        ]);
+        for (number, (digit, function_key)) in (1..).zip(digits.into_iter().zip(function_keys)) {
+            bindings.insert(SelectVariant(number), key(digit));
+            bindings.insert(Bookmark(number), key(function_key));
+            bindings.insert(
+                StoreBookmark(number),
+                vec![Binding::with(Modifier::Shift, Key(function_key))],
+            );
+        }
+        for (direction, cursor_key) in (0..).zip(cursor_keys) {
+            bindings.insert(MoveCursor(direction), key(cursor_key));
+        }
+        Self { bindings }
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every pair of actions that share a binding, except double-click actions, which may share
+    /// their button with drags and single clicks. Bound actions missing from `Action::all`, such
+    /// as custom tools, are checked too.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut actions = Action::all();
+        let mut extra: Vec<Action> = self
+            .bindings
+            .keys()
+            .filter(|action| !actions.contains(action))
+            .copied()
+            .collect();
+        extra.sort();
+        actions.extend(extra);
+        let mut conflicts = Vec::new();
+        for (index, first) in actions.iter().enumerate() {
+            for second in &actions[index + 1..] {
+                if first.is_double_click() != second.is_double_click() {
+                    continue;
+                }
+                for binding in self.get(*first) {
+                    if self.get(*second).contains(binding) {
+                        conflicts.push(Conflict {
+                            binding: *binding,
+                            actions: (*first, *second),
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Replaces the bindings of `action` on the device of `binding`, keeping those on other
+    /// devices, and returns the actions it now conflicts with. The binding is kept either way, so
+    /// conflicts can be resolved one at a time.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Vec<Action> {
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|bound| !bound.input.same_device(&binding.input));
+        bindings.push(binding);
+        self.conflicts()
+            .into_iter()
+            .filter_map(|conflict| match conflict.actions {
+                (first, other) | (other, first) if first == action => Some(other),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// Parses a settings file. Actions missing from it keep their default bindings.
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        let mut bindings = Self::default();
+        let loaded: Self = ron::from_str(source)?;
+        bindings.bindings.extend(loaded.bindings);
+        Ok(bindings)
+    }
+}
+
+/// The action waiting for a new binding, if any. The next key or button pressed replaces its
+/// bindings on that device; Escape cancels.
+#[derive(Resource, Default)]
+pub struct Rebinding {
+    pub action: Option<Action>,
+    /// Actions sharing the binding last assigned.
+    pub conflicts: Vec<Action>,
+    /// The input that was captured, cancelled rebinding or was typed into a text field is still
+    /// held and must not trigger an action yet.
+    awaiting_release: bool,
+}
+
+/// Set while a text field has keyboard focus. No action triggers while it is set, nor until the
+/// inputs held when it is cleared are released, so typing and confirming never edit the map.
+#[derive(Resource, Default)]
+pub struct TextInputFocus(pub bool);
+
+#[derive(Resource, Clone, Debug)]
+pub struct BindingsConfig {
+    /// RON settings file the bindings are read from and saved to after rebinding. Missing files
+    /// fall back to the defaults.
+    pub path: Option<PathBuf>,
+}
+
+impl Default for BindingsConfig {
+    fn default() -> Self {
+        Self {
+            path: Some(PathBuf::from("settings.ron")),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BindingsPlugin {
+    pub config: BindingsConfig,
+}
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings = self
+            .config
+            .path
+            .as_ref()
+            .filter(|path| path.exists())
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|source| {
+                        InputBindings::from_ron(&source).map_err(|error| error.to_string())
+                    })
+                    .unwrap_or_else(|error| {
+                        error!("could not read bindings from {}: {error}", path.display());
+                        InputBindings::default()
+                    })
+            })
+            .unwrap_or_default();
+        for conflict in bindings.conflicts() {
+            warn!(
+                "{} is bound to both {} and {}",
+                conflict.binding, conflict.actions.0, conflict.actions.1
+            );
+        }
+
+        app.insert_resource(self.config.clone())
+            .insert_resource(bindings)
+            .init_resource::<Input<Action>>()
+            .init_resource::<Rebinding>()
+            .init_resource::<TextInputFocus>()
+            .add_systems(
+                (capture_binding, update_actions)
+                    .chain()
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            );
+    }
+}
+
+/// Modifiers held right now, leaving out the one `input` itself belongs to.
+fn held_modifiers(keys: &Input<KeyCode>, input: Option<KeyCode>) -> Vec<Modifier> {
+    let own = input.and_then(Modifier::of);
+    Modifier::ALL
+        .into_iter()
+        .filter(|modifier| {
+            Some(*modifier) != own && modifier.keys().iter().any(|key| keys.pressed(*key))
+        })
+        .collect()
+}
+
+fn update_actions(
+    bindings: Res<InputBindings>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut rebinding: ResMut<Rebinding>,
+    text_input: Res<TextInputFocus>,
+    mut actions: ResMut<Input<Action>>,
+) {
+    actions.clear();
+    if text_input.0 {
+        rebinding.awaiting_release = true;
+    } else if rebinding.awaiting_release {
+        rebinding.awaiting_release = keys.get_pressed().next().is_some()
+            || mouse.get_pressed().next().is_some()
+            || gamepad_buttons.get_pressed().next().is_some();
+    }
+    let pressed = |binding: &Binding| {
+        let (down, key) = match binding.input {
+            InputButton::Key(key) => (keys.pressed(key), Some(key)),
+            InputButton::Mouse(button) => (mouse.pressed(button), None),
+            InputButton::Gamepad(button_type) => (
+                gamepads.iter().any(|gamepad| {
+                    gamepad_buttons.pressed(GamepadButton::new(gamepad, button_type))
+                }),
+                None,
+            ),
+        };
+        down && held_modifiers(&keys, key) == Vec::from_iter(binding.modifier)
+    };
+    for (action, action_bindings) in &bindings.bindings {
+        let down = rebinding.action.is_none()
+            && !rebinding.awaiting_release
+            && action_bindings.iter().any(pressed);
+        if down {
+            actions.press(*action);
+        } else {
+            actions.release(*action);
+        }
+    }
+}
+
+fn capture_binding(
+    config: Res<BindingsConfig>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.action = None;
+        rebinding.awaiting_release = true;
+        return;
+    }
+    // Modifier keys only become bindings of their own when released without another input.
+    let input = keys
+        .get_just_pressed()
+        .find(|key| Modifier::of(**key).is_none())
+        .map(|key| InputButton::Key(*key))
+        .or_else(|| {
+            keys.get_just_released()
+                .find(|key| Modifier::of(**key).is_some())
+                .map(|key| InputButton::Key(*key))
+        })
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| InputButton::Mouse(*button))
+        })
+        .or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| InputButton::Gamepad(button.button_type))
+        });
+    let Some(input) = input else {
+        return;
+    };
+    let key = match input {
+        InputButton::Key(key) => Some(key),
+        _ => None,
+    };
+    let binding = Binding {
+        input,
+        modifier: held_modifiers(&keys, key).first().copied(),
+    };
+    rebinding.conflicts = bindings.rebind(action, binding);
+    rebinding.action = None;
+    rebinding.awaiting_release = true;
+
+    if let Some(path) = &config.path {
+        let result = bindings
+            .to_ron()
+            .map_err(|error| error.to_string())
+            .and_then(|source| fs::write(path, source).map_err(|error| error.to_string()));
+        if let Err(error) = result {
+            error!("could not save bindings to {}: {error}", path.display());
+        }
+    }
+}
@@ -1,22 +1,189 @@
-use std::f32::consts::PI;
+use std::collections::BTreeMap;
+use std::f32::consts::{FRAC_PI_3, FRAC_PI_4, PI, TAU};
 
+use bevy::input::gamepad::GamepadAxisType;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
 use bevy::render::camera::ScalingMode;
-use bevy_mod_picking::prelude::*;
+use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
+
+use hexx::Hex;
+
+use crate::bindings_plugin::Action;
+use crate::grid_plugin::{GridConfig, GridTracker, HoveredHex, MapLoaded, Selection};
+use crate::GameState;
 
 static MAX_ANGLE: f32 = PI * 0.5 * 0.99;
 static MIN_ANGLE: f32 = 0.05;
+/// Keeps panning near the horizon from stretching drags to several times their length.
+static MIN_PAN_SINE: f32 = 0.25;
 static FOCUS: Vec3 = Vec3::new(0.0, 0.8, 0.0);
 
-pub struct CameraPlugin;
+#[derive(Resource, Clone, Debug)]
+pub struct CameraConfig {
+    /// Radians per second around the vertical axis.
+    pub yaw_speed: f32,
+    /// Radians per second towards or away from the top-down view.
+    pub pitch_speed: f32,
+    /// Radians per pixel of mouse movement while orbiting.
+    pub orbit_sensitivity: f32,
+    /// Fraction of the view height to zoom per scroll line.
+    pub zoom_sensitivity: f32,
+    /// Zoom limits, as half the height of the view at the focus in world units. This is the
+    /// orthographic `scale`; smaller is closer.
+    pub min_scale: f32,
+    pub max_scale: f32,
+    /// Start with a perspective projection instead of an orthographic one.
+    pub perspective: bool,
+    /// Vertical field of view of the perspective projection, in radians.
+    pub fov: f32,
+    pub min_fov: f32,
+    pub max_fov: f32,
+    /// Yaw step `Action::SnapYaw` turns to. 60° lines the view up with the hex corners, 30° also
+    /// stops at the edges.
+    pub snap_angle: f32,
+    /// How quickly the focus and view ease towards a new target; higher is snappier.
+    pub focus_smoothing: f32,
+    /// Longest gap between the clicks of a double-click, in seconds.
+    pub double_click_time: f32,
+    /// Move the focus along with the center of the board when it grows or a map is loaded. Never
+    /// during a drag, which would shift the hex under the pointer.
+    pub follow_board: bool,
+    /// Frame all tiles whenever a map with tiles is loaded.
+    pub frame_on_load: bool,
+    /// Room left around framed tiles, as a factor of their extent.
+    pub frame_padding: f32,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self {
+            yaw_speed: 0.8,
+            pitch_speed: 0.5,
+            orbit_sensitivity: 0.005,
+            zoom_sensitivity: 0.1,
+            min_scale: 1.0,
+            max_scale: 20.0,
+            perspective: false,
+            fov: FRAC_PI_4,
+            min_fov: 0.2,
+            max_fov: 1.8,
+            snap_angle: FRAC_PI_3,
+            focus_smoothing: 8.0,
+            double_click_time: 0.3,
+            follow_board: false,
+            frame_on_load: true,
+            frame_padding: 1.15,
+        }
+    }
+}
+
+/// The point the camera orbits around and looks at.
+#[derive(Resource)]
+pub struct CameraFocus {
+    pub position: Vec3,
+    /// Where `position` is easing towards. Set this to move the camera smoothly.
+    pub target: Vec3,
+}
+
+impl CameraFocus {
+    /// Moves both the focus and its target at once, without easing.
+    pub fn translate(&mut self, delta: Vec3) {
+        self.position += delta;
+        self.target += delta;
+    }
+}
+
+impl Default for CameraFocus {
+    fn default() -> Self {
+        Self {
+            position: FOCUS,
+            target: FOCUS,
+        }
+    }
+}
+
+/// A saved viewpoint.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CameraBookmark {
+    pub focus: Vec3,
+    /// Radians around the vertical axis, `0` looking towards negative z.
+    pub yaw: f32,
+    /// Radians above the ground plane.
+    pub pitch: f32,
+    /// Half the height of the view at the focus, see `CameraConfig::min_scale`.
+    pub zoom: f32,
+}
+
+/// Bookmarks by number, saved with the map.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct CameraBookmarks(pub BTreeMap<u8, CameraBookmark>);
+
+/// Moves and zooms the camera so that part of the map fills the view.
+#[derive(Clone, Debug)]
+pub enum FrameCamera {
+    /// Every placed tile, or the whole board if it is empty.
+    All,
+    Hexes(Vec<Hex>),
+}
+
+/// Yaw, pitch and zoom the view is easing towards, cleared by manual control.
+#[derive(Resource, Default)]
+struct ViewTarget {
+    yaw: Option<f32>,
+    pitch: Option<f32>,
+    zoom: Option<f32>,
+}
+
+#[derive(Default)]
+pub struct CameraPlugin {
+    pub config: CameraConfig,
+}
+
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(camera_setup)
-            .add_system(camera_control);
+        app.insert_resource(self.config.clone())
+            .init_resource::<CameraFocus>()
+            .init_resource::<CameraBookmarks>()
+            .init_resource::<ViewTarget>()
+            .add_event::<FrameCamera>()
+            .add_startup_system(camera_setup)
+            .add_systems(
+                (
+                    follow_board,
+                    camera_control.after(follow_board),
+                    mouse_control.after(camera_control),
+                    touch_control.after(mouse_control),
+                    gamepad_control.after(mouse_control),
+                    toggle_projection.after(mouse_control),
+                    center_on_double_click,
+                    snap_yaw,
+                    camera_bookmarks,
+                    ease_view
+                        .after(snap_yaw)
+                        .after(camera_bookmarks)
+                        .after(toggle_projection)
+                        .after(frame_camera),
+                    ease_focus
+                        .after(center_on_double_click)
+                        .after(mouse_control),
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_systems(
+                (
+                    frame_shortcuts,
+                    frame_on_load,
+                    frame_camera.after(follow_board),
+                )
+                    .chain()
+                    .in_set(OnUpdate(GameState::Playing)),
+            );
     }
 }
 
-fn camera_setup(mut commands: Commands) {
+fn camera_setup(mut commands: Commands, config: Res<CameraConfig>, focus: Res<CameraFocus>) {
     commands.spawn(PointLightBundle {
         transform: Transform::from_xyz(-2.0, 15.0, 0.0),
         point_light: PointLight {
@@ -26,66 +193,532 @@ fn camera_setup(mut commands: Commands) {
         },
         ..default()
     });
-    commands.spawn((
-        Camera3dBundle {
-            transform: Transform::from_xyz(0.0, 10.0, 12.0).looking_at(FOCUS, Vec3::Y),
-            projection: Projection::Orthographic(OrthographicProjection {
-                scale: 6.0,
-                scaling_mode: ScalingMode::FixedVertical(2.),
-                ..default()
-            }),
-            ..default()
-        },
-        RaycastPickCamera::default(),
-    ));
+    let mut transform = Transform::from_xyz(0.0, 10.0, 12.0).looking_at(focus.position, Vec3::Y);
+    let mut projection = orthographic();
+    if config.perspective {
+        projection = perspective(config.fov);
+    }
+    set_view_height(&mut projection, &mut transform, focus.position, 6.0);
+    commands.spawn(Camera3dBundle {
+        transform,
+        projection,
+        ..default()
+    });
+}
+
+fn orthographic() -> Projection {
+    Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::FixedVertical(2.),
+        ..default()
+    })
+}
+
+fn perspective(fov: f32) -> Projection {
+    Projection::Perspective(PerspectiveProjection { fov, ..default() })
+}
+
+/// Half the height of the view at the focus, in world units.
+fn view_height(projection: &Projection, transform: &Transform, focus: Vec3) -> f32 {
+    match projection {
+        Projection::Orthographic(ortho) => ortho.scale,
+        Projection::Perspective(perspective) => {
+            transform.translation.distance(focus) * (perspective.fov / 2.0).tan()
+        }
+    }
+}
+
+/// Zooms so that `height` is half the height of the view at the focus, by scaling an
+/// orthographic projection or moving a perspective camera along its line of sight.
+fn set_view_height(
+    projection: &mut Projection,
+    transform: &mut Transform,
+    focus: Vec3,
+    height: f32,
+) {
+    match projection {
+        Projection::Orthographic(ortho) => ortho.scale = height,
+        Projection::Perspective(perspective) => {
+            let distance = height / (perspective.fov / 2.0).tan();
+            transform.translation = focus + (transform.translation - focus).normalize() * distance;
+        }
+    }
+}
+
+/// Swaps the projection while keeping the framing at the focus.
+fn toggle_projection(
+    actions: Res<Input<Action>>,
+    config: Res<CameraConfig>,
+    focus: Res<CameraFocus>,
+    mut last_fov: Local<Option<f32>>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+) {
+    if !actions.just_pressed(Action::ToggleProjection) {
+        return;
+    }
+    let (mut transform, mut projection) = camera.single_mut();
+    let height = view_height(&projection, &transform, focus.position);
+    *projection = match projection.as_ref() {
+        Projection::Orthographic(_) => perspective(last_fov.unwrap_or(config.fov)),
+        Projection::Perspective(perspective) => {
+            *last_fov = Some(perspective.fov);
+            orthographic()
+        }
+    };
+    set_view_height(&mut projection, &mut transform, focus.position, height);
 }
 
 fn camera_control(
     time: Res<Time>,
-    keys: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
+    config: Res<CameraConfig>,
+    focus: Res<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
     mut camera: Query<&mut Transform, With<Camera>>,
 ) {
     let mut camera_transform = camera.single_mut();
-    if keys.pressed(KeyCode::Left) {
-        camera_transform.rotate_around(
-            Vec3::ZERO,
-            Quat::from_rotation_y(-time.delta_seconds() * 0.8),
+    let axis = |negative: Action, positive: Action| {
+        actions.pressed(positive) as i8 as f32 - actions.pressed(negative) as i8 as f32
+    };
+    let yaw = axis(Action::OrbitLeft, Action::OrbitRight) * config.yaw_speed;
+    let pitch = axis(Action::OrbitDown, Action::OrbitUp) * config.pitch_speed;
+    if yaw != 0.0 || pitch != 0.0 {
+        target.yaw = None;
+        target.pitch = None;
+    }
+    orbit(
+        &mut camera_transform,
+        focus.position,
+        yaw * time.delta_seconds(),
+        pitch * time.delta_seconds(),
+    );
+}
+
+fn mouse_control(
+    actions: Res<Input<Action>>,
+    config: Res<CameraConfig>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut focus: ResMut<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+) {
+    let (mut transform, mut projection) = camera.single_mut();
+    let drag: Vec2 = motion.iter().map(|event| event.delta).sum();
+    let scroll: f32 = wheel
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        })
+        .sum();
+
+    if actions.pressed(Action::Pan) && drag != Vec2::ZERO {
+        let window_height = windows.get_single().map_or(1.0, |window| window.height());
+        let world_per_pixel =
+            2.0 * view_height(&projection, &transform, focus.position) / window_height;
+        // Vertical drags cover more ground the flatter the view.
+        let pitch_sine = (transform.translation - focus.position)
+            .normalize()
+            .y
+            .max(MIN_PAN_SINE);
+        let right = transform.right();
+        let forward = Vec3::new(right.z, 0.0, -right.x);
+        let delta = (-right * drag.x + forward * drag.y / pitch_sine) * world_per_pixel;
+        transform.translation += delta;
+        focus.translate(delta);
+    } else if actions.pressed(Action::Orbit) && drag != Vec2::ZERO {
+        target.yaw = None;
+        target.pitch = None;
+        orbit(
+            &mut transform,
+            focus.position,
+            drag.x * config.orbit_sensitivity,
+            drag.y * config.orbit_sensitivity,
         );
     }
-    if keys.pressed(KeyCode::Right) {
-        camera_transform.rotate_around(
-            Vec3::ZERO,
-            Quat::from_rotation_y(time.delta_seconds() * 0.8),
+
+    if scroll == 0.0 {
+        return;
+    }
+    target.zoom = None;
+    let factor = (1.0 - config.zoom_sensitivity).powf(scroll);
+    let height = view_height(&projection, &transform, focus.position);
+    if actions.pressed(Action::AdjustFov) {
+        // Widen or narrow the lens while keeping the focus framed the same.
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = (perspective.fov * factor).clamp(config.min_fov, config.max_fov);
+        }
+        set_view_height(&mut projection, &mut transform, focus.position, height);
+    } else {
+        let height = (height * factor).clamp(config.min_scale, config.max_scale);
+        set_view_height(&mut projection, &mut transform, focus.position, height);
+    }
+}
+
+/// The right stick of any gamepad orbits like the orbit keys.
+fn gamepad_control(
+    time: Res<Time>,
+    config: Res<CameraConfig>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    focus: Res<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    mut camera: Query<&mut Transform, With<Camera>>,
+) {
+    let stick: Vec2 = gamepads
+        .iter()
+        .map(|gamepad| {
+            let axis = |axis_type| {
+                axes.get(GamepadAxis::new(gamepad, axis_type))
+                    .unwrap_or_default()
+            };
+            Vec2::new(
+                axis(GamepadAxisType::RightStickX),
+                axis(GamepadAxisType::RightStickY),
+            )
+        })
+        .sum();
+    if stick == Vec2::ZERO {
+        return;
+    }
+    target.yaw = None;
+    target.pitch = None;
+    orbit(
+        &mut camera.single_mut(),
+        focus.position,
+        stick.x * config.yaw_speed * time.delta_seconds(),
+        stick.y * config.pitch_speed * time.delta_seconds(),
+    );
+}
+
+/// Two fingers dragged together orbit the camera and pinching zooms. Single touches are left to
+/// the grid.
+fn touch_control(
+    touches: Res<Touches>,
+    config: Res<CameraConfig>,
+    focus: Res<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+) {
+    let fingers: Vec<_> = touches.iter().take(3).collect();
+    let [first, second] = fingers[..] else {
+        return;
+    };
+    *target = ViewTarget::default();
+    let (mut transform, mut projection) = camera.single_mut();
+
+    // Touch deltas point down the screen, like mouse motion.
+    let drag = (first.delta() + second.delta()) / 2.0;
+    orbit(
+        &mut transform,
+        focus.position,
+        drag.x * config.orbit_sensitivity,
+        drag.y * config.orbit_sensitivity,
+    );
+
+    let spread = first.position().distance(second.position());
+    let previous_spread = first
+        .previous_position()
+        .distance(second.previous_position());
+    if spread > 0.0 && previous_spread > 0.0 {
+        let height =
+            view_height(&projection, &transform, focus.position) * previous_spread / spread;
+        let height = height.clamp(config.min_scale, config.max_scale);
+        set_view_height(&mut projection, &mut transform, focus.position, height);
+    }
+}
+
+/// Points the focus at the top of a column when it is double-clicked.
+fn center_on_double_click(
+    time: Res<Time>,
+    actions: Res<Input<Action>>,
+    config: Res<CameraConfig>,
+    grid_config: Option<Res<GridConfig>>,
+    tracker: Option<Res<GridTracker>>,
+    hovered: Option<Res<HoveredHex>>,
+    mut last_click: Local<Option<(Hex, f32)>>,
+    mut focus: ResMut<CameraFocus>,
+) {
+    if !actions.just_pressed(Action::CenterOnTile) {
+        return;
+    }
+    let hex = hovered.and_then(|hovered| hovered.0).map(|hit| hit.hex);
+    let (Some(grid_config), Some(tracker), Some(hex)) = (grid_config, tracker, hex) else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    match last_click.replace((hex, now)) {
+        Some((last_hex, at)) if last_hex == hex && now - at <= config.double_click_time => {
+            let height = tracker.height(hex).unwrap_or(0) as f32 * grid_config.layer_height;
+            let center = tracker.layout.hex_to_world_pos(hex);
+            focus.target = Vec3::new(center.x, height, center.y);
+            *last_click = None;
+        }
+        _ => {}
+    }
+}
+
+/// Eases the focus towards its target, carrying the camera along so the view angle is kept.
+fn ease_focus(
+    time: Res<Time>,
+    config: Res<CameraConfig>,
+    mut focus: ResMut<CameraFocus>,
+    mut camera: Query<&mut Transform, With<Camera>>,
+) {
+    let remaining = focus.target - focus.position;
+    if remaining == Vec3::ZERO {
+        return;
+    }
+    let delta = if remaining.length_squared() < 1e-6 {
+        remaining
+    } else {
+        remaining * (1.0 - (-config.focus_smoothing * time.delta_seconds()).exp())
+    };
+    focus.position += delta;
+    for mut transform in &mut camera {
+        transform.translation += delta;
+    }
+}
+
+/// Turns the camera to the nearest hex-aligned yaw.
+fn snap_yaw(
+    actions: Res<Input<Action>>,
+    config: Res<CameraConfig>,
+    focus: Res<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    if !actions.just_pressed(Action::SnapYaw) {
+        return;
+    }
+    let (yaw, _) = yaw_pitch(camera.single(), focus.position);
+    target.yaw = Some((yaw / config.snap_angle).round() * config.snap_angle);
+}
+
+fn camera_bookmarks(
+    actions: Res<Input<Action>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut focus: ResMut<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    camera: Query<(&Transform, &Projection), With<Camera>>,
+) {
+    let (transform, projection) = camera.single();
+    for number in 1..=9 {
+        if !actions.just_pressed(Action::StoreBookmark(number)) {
+            continue;
+        }
+        let (yaw, pitch) = yaw_pitch(transform, focus.position);
+        bookmarks.0.insert(
+            number,
+            CameraBookmark {
+                focus: focus.position,
+                yaw,
+                pitch,
+                zoom: view_height(projection, transform, focus.position),
+            },
         );
     }
-    if keys.pressed(KeyCode::Up) {
-        let camera_direction = (camera_transform.translation - FOCUS).normalize();
-        let theta = camera_direction.y.asin();
-        if theta < MAX_ANGLE {
-            let rotation_axis_x = -camera_direction.z;
-            let rotation_axis_z = camera_direction.x;
-            camera_transform.rotate_around(
-                Vec3::ZERO,
-                Quat::from_axis_angle(
-                    Vec3::new(rotation_axis_x, 0.0, rotation_axis_z).normalize(),
-                    time.delta_seconds() * 0.5,
-                ),
-            );
+    let recalled = (1..=9).find(|number| actions.just_pressed(Action::Bookmark(*number)));
+    if let Some(bookmark) = recalled.and_then(|number| bookmarks.0.get(&number)) {
+        focus.target = bookmark.focus;
+        *target = ViewTarget {
+            yaw: Some(bookmark.yaw),
+            pitch: Some(bookmark.pitch),
+            zoom: Some(bookmark.zoom),
+        };
+    }
+}
+
+fn frame_shortcuts(
+    actions: Res<Input<Action>>,
+    selection: Option<Res<Selection>>,
+    mut frame: EventWriter<FrameCamera>,
+) {
+    if actions.just_pressed(Action::FrameAll) {
+        frame.send(FrameCamera::All);
+    }
+    if actions.just_pressed(Action::FrameSelection) {
+        if let Some(selection) = selection.filter(|selection| !selection.0.is_empty()) {
+            frame.send(FrameCamera::Hexes(selection.0.iter().copied().collect()));
         }
     }
-    if keys.pressed(KeyCode::Down) {
-        let camera_direction = (camera_transform.translation - FOCUS).normalize();
-        let theta = camera_direction.y.asin();
-        if theta > MIN_ANGLE {
-            let rotation_axis_x = -camera_direction.z;
-            let rotation_axis_z = camera_direction.x;
-            camera_transform.rotate_around(
-                Vec3::ZERO,
-                Quat::from_axis_angle(
-                    Vec3::new(rotation_axis_x, 0.0, rotation_axis_z).normalize(),
-                    -time.delta_seconds() * 0.5,
-                ),
-            );
+}
+
+fn frame_on_load(
+    config: Res<CameraConfig>,
+    mut loaded: EventReader<MapLoaded>,
+    mut frame: EventWriter<FrameCamera>,
+) {
+    if loaded.iter().any(|event| event.tile_count > 0) && config.frame_on_load {
+        frame.send(FrameCamera::All);
+    }
+}
+
+/// Eases the focus to the center of the framed hexes and the zoom to fit their bounding box as
+/// seen from the current camera angle.
+fn frame_camera(
+    mut events: EventReader<FrameCamera>,
+    config: Res<CameraConfig>,
+    grid_config: Option<Res<GridConfig>>,
+    tracker: Option<Res<GridTracker>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut focus: ResMut<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    let Some(event) = events.iter().last() else {
+        return;
+    };
+    let (Some(grid_config), Some(tracker)) = (grid_config, tracker) else {
+        return;
+    };
+    let hexes: Vec<Hex> = match event {
+        FrameCamera::All => {
+            let placed: Vec<Hex> = tracker
+                .columns
+                .iter()
+                .filter(|(_, column)| !column.is_empty())
+                .map(|(hex, _)| *hex)
+                .collect();
+            if placed.is_empty() {
+                tracker.columns.keys().copied().collect()
+            } else {
+                placed
+            }
+        }
+        FrameCamera::Hexes(hexes) => hexes.clone(),
+    };
+    let Some((min, max)) = tracker.bounds(hexes, grid_config.layer_height) else {
+        return;
+    };
+
+    let aspect = windows
+        .get_single()
+        .map_or(1.0, |window| window.width() / window.height());
+    let to_view = camera.single().rotation.inverse();
+    let half_extent = (max - min) / 2.0;
+    let fit = (0..8)
+        .map(|corner| {
+            let sign = |bit: u32| if corner & bit == 0 { -1.0 } else { 1.0 };
+            to_view * (Vec3::new(sign(1), sign(2), sign(4)) * half_extent)
+        })
+        .map(|corner| corner.y.abs().max(corner.x.abs() / aspect))
+        .fold(0.0, f32::max);
+
+    focus.target = (min + max) / 2.0;
+    target.zoom = Some((fit * config.frame_padding).clamp(config.min_scale, config.max_scale));
+}
+
+/// Eases yaw, pitch and zoom towards their targets, taking the short way around for yaw.
+fn ease_view(
+    time: Res<Time>,
+    config: Res<CameraConfig>,
+    focus: Res<CameraFocus>,
+    mut target: ResMut<ViewTarget>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+) {
+    if target.yaw.is_none() && target.pitch.is_none() && target.zoom.is_none() {
+        return;
+    }
+    let (mut transform, mut projection) = camera.single_mut();
+    let t = 1.0 - (-config.focus_smoothing * time.delta_seconds()).exp();
+    let step = |remaining: f32| {
+        if remaining.abs() < 1e-3 {
+            remaining
+        } else {
+            remaining * t
+        }
+    };
+
+    let (yaw, pitch) = yaw_pitch(&transform, focus.position);
+    let yaw_remaining = target
+        .yaw
+        .map_or(0.0, |target| (target - yaw + PI).rem_euclid(TAU) - PI);
+    let pitch_remaining = target
+        .pitch
+        .map_or(0.0, |target| target.clamp(MIN_ANGLE, MAX_ANGLE) - pitch);
+    orbit(
+        &mut transform,
+        focus.position,
+        step(yaw_remaining),
+        step(pitch_remaining),
+    );
+    if yaw_remaining.abs() < 1e-3 {
+        target.yaw = None;
+    }
+    if pitch_remaining.abs() < 1e-3 {
+        target.pitch = None;
+    }
+
+    if let Some(zoom) = target.zoom {
+        let height = view_height(&projection, &transform, focus.position);
+        let zoom = zoom.clamp(config.min_scale, config.max_scale);
+        set_view_height(
+            &mut projection,
+            &mut transform,
+            focus.position,
+            height + step(zoom - height),
+        );
+        if (zoom - height).abs() < 1e-3 {
+            target.zoom = None;
         }
     }
 }
+
+/// Yaw and pitch of the camera around `focus`, as used by `CameraBookmark`.
+fn yaw_pitch(transform: &Transform, focus: Vec3) -> (f32, f32) {
+    let direction = (transform.translation - focus).normalize();
+    (direction.x.atan2(direction.z), direction.y.asin())
+}
+
+/// Rotates the camera around `focus` by `yaw` radians about the vertical axis and `pitch`
+/// radians towards the top-down view, keeping the pitch within `MIN_ANGLE` and `MAX_ANGLE`.
+fn orbit(transform: &mut Transform, focus: Vec3, yaw: f32, pitch: f32) {
+    if yaw != 0.0 {
+        transform.rotate_around(focus, Quat::from_rotation_y(yaw));
+    }
+    if pitch != 0.0 {
+        let camera_direction = (transform.translation - focus).normalize();
+        let theta = camera_direction.y.asin();
+        let pitch = (theta + pitch).clamp(MIN_ANGLE, MAX_ANGLE) - theta;
+        let axis = Vec3::new(-camera_direction.z, 0.0, camera_direction.x).normalize();
+        transform.rotate_around(focus, Quat::from_axis_angle(axis, pitch));
+    }
+}
+
+/// Shifts the camera along with the center of the board, leaving any panning intact.
+fn follow_board(
+    config: Res<CameraConfig>,
+    tracker: Option<Res<GridTracker>>,
+    mut loaded: EventReader<MapLoaded>,
+    mut pending_load: Local<bool>,
+    // Board size and center when the camera last moved.
+    mut last: Local<Option<(usize, Vec2)>>,
+    mut focus: ResMut<CameraFocus>,
+    mut camera: Query<&mut Transform, With<Camera>>,
+) {
+    // A load during a drag is caught up with once the drag ends.
+    *pending_load |= loaded.iter().count() > 0;
+    let Some(tracker) = tracker.filter(|tracker| config.follow_board && tracker.is_changed())
+    else {
+        return;
+    };
+    let grown = last.map(|(count, _)| count) != Some(tracker.columns.len());
+    if tracker.drag_layer.is_some() || !(grown || *pending_load) {
+        return;
+    }
+    *pending_load = false;
+    let center = tracker.board_center();
+    let delta = match last.replace((tracker.columns.len(), center)) {
+        Some((_, last)) => Vec3::new(center.x - last.x, 0.0, center.y - last.y),
+        None => Vec3::new(center.x, FOCUS.y, center.y) - focus.position,
+    };
+    if delta.length_squared() < f32::EPSILON {
+        return;
+    }
+    for mut transform in &mut camera {
+        transform.translation += delta;
+    }
+    focus.translate(delta);
+}
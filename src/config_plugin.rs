@@ -1,12 +1,12 @@
+use bevy::input::InputPlugin;
 use bevy::prelude::*;
 use bevy::window::{PresentMode, WindowResolution};
-use bevy_mod_picking::DefaultPickingPlugins;
 
 #[cfg(debug_assertions)]
 use {
+    crate::bindings_plugin::Action,
     crate::GameState,
     bevy::diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
-    bevy::input::common_conditions::input_toggle_active,
     bevy::window::PrimaryWindow,
     bevy_debug_text_overlay::{screen_print, OverlayPlugin},
     bevy_inspector_egui::quick::WorldInspectorPlugin,
@@ -20,26 +20,70 @@ pub const HEIGHT: f32 = WIDTH * ASPECT_RATIO;
 #[derive(Resource, Default)]
 pub struct DebugOptions {
     printed_info_enabled: bool,
+    inspector_enabled: bool,
 }
 
 #[derive(Component)]
 pub struct CameraFlag;
 
-pub struct ConfigPlugin;
+#[derive(Resource, Clone, Debug)]
+pub struct AppConfig {
+    pub title: String,
+    pub width: f32,
+    pub height: f32,
+    pub clear_color: Color,
+    /// Run without a window or renderer, e.g. on build machines or in tests. Only the grid logic,
+    /// assets and events are available; camera and UI plugins must not be added.
+    pub headless: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            title: "hex_tiles".to_string(),
+            width: WIDTH,
+            height: HEIGHT,
+            clear_color: Color::rgb(0.93, 0.86, 0.86),
+            headless: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ConfigPlugin {
+    pub config: AppConfig,
+}
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone());
+
+        if self.config.headless {
+            app.add_plugins(MinimalPlugins)
+                .add_plugin(AssetPlugin::default())
+                .add_plugin(InputPlugin)
+                .add_plugin(TransformPlugin)
+                .add_plugin(HierarchyPlugin)
+                .add_asset::<Mesh>()
+                .add_asset::<Image>()
+                .add_asset::<StandardMaterial>();
+            return;
+        }
+
         app.insert_resource(Msaa::Sample4)
-            .insert_resource(ClearColor(Color::rgb(0.93, 0.86, 0.86)))
+            .insert_resource(ClearColor(self.config.clear_color))
             .add_plugins(
                 DefaultPlugins
                     .set(WindowPlugin {
                         primary_window: Some(Window {
-                            title: "hex_tiles".to_string(),
+                            title: self.config.title.clone(),
                             canvas: Some("#bevy".to_owned()),
                             fit_canvas_to_parent: true,
                             present_mode: PresentMode::AutoVsync,
-                            resolution: WindowResolution::new(WIDTH, HEIGHT),
+                            resolution: WindowResolution::new(
+                                self.config.width,
+                                self.config.height,
+                            ),
                             ..default()
                         }),
                         ..default()
@@ -49,17 +93,16 @@ impl Plugin for ConfigPlugin {
                         ..default()
                     })
                     .set(ImagePlugin::default_nearest()),
-            )
-            .add_plugins(DefaultPickingPlugins);
+            );
 
         #[cfg(debug_assertions)]
         {
             app.insert_resource(DebugOptions::default())
                 .add_plugin(OverlayPlugin::default())
-                .add_plugin(FrameTimeDiagnosticsPlugin::default())
+                .add_plugin(FrameTimeDiagnosticsPlugin)
                 .add_plugin(
                     WorldInspectorPlugin::default()
-                        .run_if(input_toggle_active(false, KeyCode::Key2)),
+                        .run_if(|options: Res<DebugOptions>| options.inspector_enabled),
                 )
                 .add_system(debug_toggle_system)
                 .add_system(debug_system);
@@ -93,8 +136,11 @@ fn debug_system(
 }
 
 #[cfg(debug_assertions)]
-fn debug_toggle_system(input: Res<Input<KeyCode>>, mut debug_options: ResMut<DebugOptions>) {
-    if input.just_pressed(KeyCode::Key1) {
+fn debug_toggle_system(actions: Res<Input<Action>>, mut debug_options: ResMut<DebugOptions>) {
+    if actions.just_pressed(Action::ToggleDebugInfo) {
         debug_options.printed_info_enabled = !debug_options.printed_info_enabled;
     }
+    if actions.just_pressed(Action::ToggleInspector) {
+        debug_options.inspector_enabled = !debug_options.inspector_enabled;
+    }
 }
@@ -0,0 +1,330 @@
+use bevy::prelude::*;
+use bevy::render::mesh::Indices;
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy::utils::{HashMap, HashSet};
+use hexx::{Direction, Hex, HexLayout};
+
+use super::{
+    GridConfig, GridTracker, MapLoaded, Tile, TilePlaced, TileRecolored, TileRemoved, TileVariant,
+};
+
+/// Height of the base plate under every board hex, in layers.
+const PLATE_HEIGHT: f32 = 0.1;
+
+/// Marks an entity holding the merged mesh of one chunk for one material.
+#[derive(Component)]
+pub struct ChunkMesh {
+    pub chunk: IVec2,
+    /// `None` for the base plates.
+    pub variant: Option<TileVariant>,
+}
+
+#[derive(Resource, Default)]
+pub struct ChunkTracker {
+    pub entities: HashMap<(IVec2, Option<TileVariant>), Entity>,
+    pub dirty: HashSet<IVec2>,
+    pub plate_material: Handle<StandardMaterial>,
+}
+
+/// The chunk containing `hex`. Chunks are `size` by `size` parallelograms in axial coordinates.
+pub fn chunk_of(hex: Hex, size: u32) -> IVec2 {
+    let size = size as i32;
+    IVec2::new(hex.x.div_euclid(size), hex.y.div_euclid(size))
+}
+
+pub fn chunk_hexes(chunk: IVec2, size: u32) -> impl Iterator<Item = Hex> {
+    let size = size as i32;
+    (0..size)
+        .flat_map(move |x| (0..size).map(move |y| Hex::new(chunk.x * size + x, chunk.y * size + y)))
+}
+
+/// Builds the merged meshes of a chunk, one per material. The faces two tiles share are left out,
+/// and so are bottoms and covered tops. Left-out faces would leave gaps through the `margin`
+/// around each tile, so they are stretched to the hex outline or the layer above instead.
+pub struct ChunkMeshBuilder<'a> {
+    pub layout: &'a HexLayout,
+    pub columns: &'a HashMap<Hex, Vec<Tile>>,
+    pub layer_height: f32,
+    pub margin: f32,
+}
+
+impl<'a> ChunkMeshBuilder<'a> {
+    pub fn build(&self, chunk: IVec2, size: u32) -> HashMap<Option<TileVariant>, Mesh> {
+        let edges = HexEdges::new(self.layout);
+        let mut parts: HashMap<Option<TileVariant>, MeshParts> = HashMap::new();
+        for hex in chunk_hexes(chunk, size) {
+            let Some(column) = self.columns.get(&hex) else {
+                continue;
+            };
+            let center = self.layout.hex_to_world_pos(hex);
+
+            // Plates keep their margin all round, it draws the board's grid, so their sides only
+            // meet when there is no margin.
+            let plate = parts.entry(None).or_default();
+            let plate_corners = edges.corners(center, [false; 6], self.margin);
+            let plate_top = PLATE_HEIGHT * self.layer_height * (1.0 - self.margin);
+            plate.top(center, &plate_corners, plate_top);
+            for (edge, direction) in edges.directions.iter().enumerate() {
+                if self.margin > 0.0 || !self.columns.contains_key(&hex.neighbor(*direction)) {
+                    plate.side(&plate_corners, edge, 0.0, plate_top);
+                }
+            }
+
+            let joined = |layer: usize| {
+                edges.directions.map(|direction| {
+                    self.columns
+                        .get(&hex.neighbor(direction))
+                        .is_some_and(|neighbor| self.meet(column.len(), neighbor.len(), layer))
+                })
+            };
+            for (layer, tile) in column.iter().enumerate() {
+                let bottom = layer as f32 * self.layer_height;
+                let top = self.tile_top(layer, column.len());
+                let sides = joined(layer);
+                let corners = edges.corners(center, sides, self.margin);
+                let mesh = parts.entry(Some(tile.variant.clone())).or_default();
+                // A covered top still shows where the tile above is narrower.
+                let above = (layer + 1 < column.len()).then(|| joined(layer + 1));
+                if above != Some(sides) {
+                    mesh.top(center, &corners, top);
+                }
+                for (edge, joined) in sides.into_iter().enumerate() {
+                    if !joined {
+                        mesh.side(&corners, edge, bottom, top);
+                    }
+                }
+            }
+        }
+        parts
+            .into_iter()
+            .filter(|(_, parts)| !parts.indices.is_empty())
+            .map(|(variant, parts)| (variant, parts.into_mesh()))
+            .collect()
+    }
+
+    /// Tiles fill their layer, except the top one, which leaves the margin free above it.
+    fn tile_top(&self, layer: usize, height: usize) -> f32 {
+        let top = (layer + 1) as f32 * self.layer_height;
+        if layer + 1 < height {
+            top
+        } else {
+            top - self.layer_height * self.margin
+        }
+    }
+
+    /// Whether the sides of two neighboring columns at `layer` cover each other exactly: both
+    /// have a tile there, and both tiles reach equally high.
+    fn meet(&self, height: usize, neighbor: usize, layer: usize) -> bool {
+        height > layer
+            && neighbor > layer
+            && self.tile_top(layer, height) == self.tile_top(layer, neighbor)
+    }
+}
+
+/// The six edges of a hex, edge `i` running from corner `i` to corner `i + 1` of
+/// `layout.hex_corners`.
+struct HexEdges {
+    /// The neighbor each edge faces.
+    directions: [Direction; 6],
+    /// Outward unit normal of each edge.
+    normals: [Vec2; 6],
+    /// Distance from the center to the edges.
+    apothem: f32,
+}
+
+impl HexEdges {
+    fn new(layout: &HexLayout) -> Self {
+        let corners = layout.hex_corners(Hex::ZERO);
+        let center = layout.hex_to_world_pos(Hex::ZERO);
+        let middles =
+            [0, 1, 2, 3, 4, 5].map(|edge| (corners[edge] + corners[(edge + 1) % 6]) / 2.0);
+        Self {
+            directions: middles.map(|middle| {
+                let neighbor = layout.world_pos_to_hex(center + (middle - center) * 2.0);
+                Hex::ZERO.neighbor_direction(neighbor).unwrap()
+            }),
+            normals: middles.map(|middle| (middle - center).normalize()),
+            apothem: (middles[0] - center).length(),
+        }
+    }
+
+    /// Corners of the hex at `center`, with the edges that are not `joined` moved inwards by
+    /// `margin`.
+    fn corners(&self, center: Vec2, joined: [bool; 6], margin: f32) -> [Vec2; 6] {
+        let distances = joined.map(|joined| {
+            if joined {
+                self.apothem
+            } else {
+                self.apothem * (1.0 - margin)
+            }
+        });
+        // Corner `i` is where the lines of edges `i - 1` and `i` cross.
+        [0, 1, 2, 3, 4, 5].map(|corner| {
+            let previous = (corner + 5) % 6;
+            let (a, b) = (self.normals[previous], self.normals[corner]);
+            let (da, db) = (distances[previous], distances[corner]);
+            let determinant = a.perp_dot(b);
+            center + Vec2::new(da * b.y - db * a.y, a.x * db - b.x * da) / determinant
+        })
+    }
+}
+
+#[derive(Default)]
+struct MeshParts {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+impl MeshParts {
+    fn top(&mut self, center: Vec2, corners: &[Vec2; 6], height: f32) {
+        let start = self.positions.len() as u32;
+        self.push(
+            Vec3::new(center.x, height, center.y),
+            Vec3::Y,
+            Vec2::splat(0.5),
+        );
+        for corner in corners {
+            let uv = (*corner - center) * 0.5 + Vec2::splat(0.5);
+            self.push(Vec3::new(corner.x, height, corner.y), Vec3::Y, uv);
+        }
+        for i in 0..6 {
+            self.triangle(start, start + 1 + i, start + 1 + (i + 1) % 6, Vec3::Y);
+        }
+    }
+
+    fn side(&mut self, corners: &[Vec2; 6], edge: usize, bottom: f32, top: f32) {
+        let (a, b) = (corners[edge], corners[(edge + 1) % 6]);
+        let normal = {
+            let outward = (a + b) / 2.0 - corners.iter().copied().sum::<Vec2>() / 6.0;
+            Vec3::new(outward.x, 0.0, outward.y).normalize()
+        };
+        let start = self.positions.len() as u32;
+        self.push(Vec3::new(a.x, bottom, a.y), normal, Vec2::new(0.0, 1.0));
+        self.push(Vec3::new(b.x, bottom, b.y), normal, Vec2::new(1.0, 1.0));
+        self.push(Vec3::new(b.x, top, b.y), normal, Vec2::new(1.0, 0.0));
+        self.push(Vec3::new(a.x, top, a.y), normal, Vec2::new(0.0, 0.0));
+        self.triangle(start, start + 1, start + 2, normal);
+        self.triangle(start, start + 2, start + 3, normal);
+    }
+
+    fn push(&mut self, position: Vec3, normal: Vec3, uv: Vec2) {
+        self.positions.push(position.to_array());
+        self.normals.push(normal.to_array());
+        self.uvs.push(uv.to_array());
+    }
+
+    /// Adds a triangle wound counter-clockwise when seen from the side `normal` points to.
+    fn triangle(&mut self, a: u32, b: u32, c: u32, normal: Vec3) {
+        let [pa, pb, pc] = [a, b, c].map(|i| Vec3::from(self.positions[i as usize]));
+        if (pb - pa).cross(pc - pa).dot(normal) >= 0.0 {
+            self.indices.extend([a, b, c]);
+        } else {
+            self.indices.extend([a, c, b]);
+        }
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.set_indices(Some(Indices::U32(self.indices)));
+        mesh
+    }
+}
+
+pub fn mark_dirty_chunks(
+    tracker: Res<GridTracker>,
+    config: Res<GridConfig>,
+    mut chunks: ResMut<ChunkTracker>,
+    mut placed: EventReader<TilePlaced>,
+    mut removed: EventReader<TileRemoved>,
+    mut recolored: EventReader<TileRecolored>,
+    mut loaded: EventReader<MapLoaded>,
+) {
+    let mut mark_around = |hex: Hex| {
+        chunks.dirty.insert(chunk_of(hex, config.chunk_size));
+        for neighbor in hex.all_neighbors() {
+            chunks.dirty.insert(chunk_of(neighbor, config.chunk_size));
+        }
+    };
+    placed.iter().for_each(|event| mark_around(event.hex));
+    removed.iter().for_each(|event| mark_around(event.hex));
+    recolored.iter().for_each(|event| mark_around(event.hex));
+    if loaded.iter().count() > 0 {
+        let all: HashSet<IVec2> = tracker
+            .columns
+            .keys()
+            .map(|hex| chunk_of(*hex, config.chunk_size))
+            .collect();
+        chunks.dirty.extend(all);
+        let stale: Vec<_> = chunks.entities.keys().cloned().collect();
+        chunks
+            .dirty
+            .extend(stale.into_iter().map(|(chunk, _)| chunk));
+    }
+}
+
+pub fn rebuild_chunks(
+    mut commands: Commands,
+    mut tracker: ResMut<GridTracker>,
+    config: Res<GridConfig>,
+    mut chunks: ResMut<ChunkTracker>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if chunks.dirty.is_empty() {
+        return;
+    }
+    let builder = ChunkMeshBuilder {
+        layout: &tracker.layout,
+        columns: &tracker.columns,
+        layer_height: config.layer_height,
+        margin: config.margin,
+    };
+    let rebuilt: Vec<_> = chunks
+        .dirty
+        .drain()
+        .map(|chunk| (chunk, builder.build(chunk, config.chunk_size)))
+        .collect();
+    for (chunk, mut built) in rebuilt {
+        let stale: Vec<_> = chunks
+            .entities
+            .keys()
+            .filter(|(key, variant)| *key == chunk && !built.contains_key(variant))
+            .cloned()
+            .collect();
+        for key in stale {
+            if let Some(entity) = chunks.entities.remove(&key) {
+                commands.entity(entity).despawn();
+            }
+        }
+        for (variant, mesh) in built.drain() {
+            let mesh = meshes.add(mesh);
+            if let Some(&entity) = chunks.entities.get(&(chunk, variant.clone())) {
+                commands.entity(entity).insert(mesh);
+                continue;
+            }
+            let material = match &variant {
+                Some(variant) => tracker.tile_material(variant, &mut materials),
+                None => chunks.plate_material.clone(),
+            };
+            let entity = commands
+                .spawn((
+                    PbrBundle {
+                        mesh,
+                        material,
+                        ..default()
+                    },
+                    ChunkMesh {
+                        chunk,
+                        variant: variant.clone(),
+                    },
+                ))
+                .id();
+            chunks.entities.insert((chunk, variant), entity);
+        }
+    }
+}
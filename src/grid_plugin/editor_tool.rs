@@ -0,0 +1,206 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use hexx::Hex;
+
+use super::tile_events::{place_tile, recolor_tile, remove_top_tile};
+use super::{
+    ActiveTool, EditHistory, GridTracker, HexHit, Selection, Tile, TilePlaced, TileRecolored,
+    TileRemoved, TileVariant, Tool,
+};
+
+/// Something pointer presses and drags on the board can do. The built-in tools implement it too;
+/// add your own with `App::add_editor_tool` and switch to it through `ActiveTool` or a
+/// `UseTool` binding.
+///
+/// Every callback gets the hex under the pointer, the layer and face that were hit, and a
+/// `MapAccess` to read and change the map. Changes made through it send the usual map events, so
+/// chunks are rebuilt and the edits can be undone. Any mix of edits can be made in one callback;
+/// the edits of a frame are undone together, latest first.
+pub trait EditorTool: Send + Sync + 'static {
+    /// Shown in the tool bar, and used to look the tool up with `EditorTools::find`.
+    fn name(&self) -> &str;
+
+    /// The pointer moved onto a hex without being pressed.
+    fn hover(&mut self, _hit: &HexHit, _map: &mut MapAccess) {}
+
+    /// The pointer was pressed on a board hex.
+    fn press(&mut self, _hit: &HexHit, _map: &mut MapAccess) {}
+
+    /// The pointer, still pressed, entered another board hex.
+    fn drag(&mut self, _hit: &HexHit, _map: &mut MapAccess) {}
+
+    /// The pointer was released, over `hit` or over the last hex it hit.
+    fn release(&mut self, _hit: &HexHit, _map: &mut MapAccess) {}
+}
+
+/// Read and write access to the map for `EditorTool`s.
+#[derive(SystemParam)]
+pub struct MapAccess<'w, 's> {
+    commands: Commands<'w, 's>,
+    tracker: ResMut<'w, GridTracker>,
+    selection: ResMut<'w, Selection>,
+    active_tool: ResMut<'w, ActiveTool>,
+    history: ResMut<'w, EditHistory>,
+    tile_placed: EventWriter<'w, TilePlaced>,
+    tile_removed: EventWriter<'w, TileRemoved>,
+    tile_recolored: EventWriter<'w, TileRecolored>,
+}
+
+impl<'w, 's> MapAccess<'w, 's> {
+    pub fn tracker(&self) -> &GridTracker {
+        &self.tracker
+    }
+
+    /// Number of tiles on `hex`, or `None` if it is not part of the board.
+    pub fn height(&self, hex: Hex) -> Option<u32> {
+        self.tracker.height(hex)
+    }
+
+    /// The tiles stacked on `hex`, bottom first.
+    pub fn column(&self, hex: Hex) -> &[Tile] {
+        self.tracker.columns.get(&hex).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn top_variant(&self, hex: Hex) -> Option<&TileVariant> {
+        self.column(hex).last().map(|tile| &tile.variant)
+    }
+
+    /// Height of the column the pointer was pressed on, while it is held.
+    pub fn drag_layer(&self) -> Option<u32> {
+        self.tracker.drag_layer
+    }
+
+    /// The variant selected in the palette.
+    pub fn current_variant(&self) -> &TileVariant {
+        &self.tracker.current_tile_variant
+    }
+
+    pub fn set_current_variant(&mut self, variant: TileVariant) {
+        self.tracker.current_tile_variant = variant;
+    }
+
+    /// Stacks a tile on `hex`. Returns `false` if `hex` is not on the board.
+    pub fn place(&mut self, hex: Hex, variant: TileVariant) -> bool {
+        if self.tracker.height(hex).is_none() {
+            return false;
+        }
+        let placed = place_tile(&mut self.commands, &mut self.tracker, hex, variant);
+        self.history.log(&placed);
+        self.tile_placed.send(placed);
+        true
+    }
+
+    /// Takes the top tile off `hex`. Returns `false` if there is none.
+    pub fn remove_top(&mut self, hex: Hex) -> bool {
+        let Some(removed) = remove_top_tile(&mut self.commands, &mut self.tracker, hex) else {
+            return false;
+        };
+        self.history.log(&removed);
+        self.tile_removed.send(removed);
+        true
+    }
+
+    /// Changes the tile at `layer` of `hex` to `variant`. Returns `false` if there is no such
+    /// tile or it already has that variant.
+    pub fn recolor(&mut self, hex: Hex, layer: u32, variant: TileVariant) -> bool {
+        let recolored = recolor_tile(&mut self.commands, &mut self.tracker, hex, layer, variant);
+        let Some(recolored) = recolored else {
+            return false;
+        };
+        self.history.log(&recolored);
+        self.tile_recolored.send(recolored);
+        true
+    }
+
+    pub fn selection(&mut self) -> &mut Selection {
+        &mut self.selection
+    }
+
+    /// The tool in use. Unlike `active_tool`, this leaves `ActiveTool` unchanged for change
+    /// detection.
+    pub fn tool(&self) -> Tool {
+        self.active_tool.tool
+    }
+
+    pub fn active_tool(&mut self) -> &mut ActiveTool {
+        &mut self.active_tool
+    }
+
+    pub(super) fn tracker_mut(&mut self) -> &mut GridTracker {
+        &mut self.tracker
+    }
+}
+
+/// Every tool pointer events can be dispatched to, in tool bar order.
+#[derive(Resource, Default)]
+pub struct EditorTools {
+    tools: Vec<(Tool, Box<dyn EditorTool>)>,
+    /// Number of custom tools registered so far.
+    custom: usize,
+}
+
+impl EditorTools {
+    /// Adds `editor_tool` under `tool`, replacing the one registered there before.
+    pub fn insert(&mut self, tool: Tool, editor_tool: impl EditorTool) {
+        let entry = (tool, Box::new(editor_tool) as Box<dyn EditorTool>);
+        match self
+            .tools
+            .iter_mut()
+            .find(|(existing, _)| *existing == tool)
+        {
+            Some(existing) => *existing = entry,
+            None => self.tools.push(entry),
+        }
+    }
+
+    /// Adds a tool of your own and returns the `Tool::Custom` it can be selected with. Panics if
+    /// 256 custom tools are registered already.
+    pub fn register(&mut self, editor_tool: impl EditorTool) -> Tool {
+        let number = u8::try_from(self.custom).expect("at most 256 custom tools can be registered");
+        let tool = Tool::Custom(number);
+        self.custom += 1;
+        self.insert(tool, editor_tool);
+        tool
+    }
+
+    /// Moves the tools of `other` after these, keeping their numbers.
+    pub(super) fn append(&mut self, other: EditorTools) {
+        self.tools.extend(other.tools);
+        self.custom = self.custom.max(other.custom);
+    }
+
+    pub fn get_mut(&mut self, tool: Tool) -> Option<&mut (dyn EditorTool + 'static)> {
+        self.tools
+            .iter_mut()
+            .find(|(existing, _)| *existing == tool)
+            .map(|(_, editor_tool)| editor_tool.as_mut())
+    }
+
+    /// The registered tools and their names, in tool bar order.
+    pub fn iter(&self) -> impl Iterator<Item = (Tool, &str)> {
+        self.tools
+            .iter()
+            .map(|(tool, editor_tool)| (*tool, editor_tool.name()))
+    }
+
+    /// The tool registered under `name`.
+    pub fn find(&self, name: &str) -> Option<Tool> {
+        self.iter()
+            .find(|(_, tool_name)| *tool_name == name)
+            .map(|(tool, _)| tool)
+    }
+}
+
+/// Registers `EditorTool`s with the app, before or after `GridPlugin` is added.
+pub trait EditorToolAppExt {
+    fn add_editor_tool(&mut self, editor_tool: impl EditorTool) -> &mut Self;
+}
+
+impl EditorToolAppExt for App {
+    fn add_editor_tool(&mut self, editor_tool: impl EditorTool) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(EditorTools::default)
+            .register(editor_tool);
+        self
+    }
+}
@@ -0,0 +1,179 @@
+use std::f32::consts::FRAC_PI_3;
+
+use bevy::input::gamepad::GamepadAxisType;
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::bindings_plugin::Action;
+
+use super::{EraseTile, GridConfig, GridTracker, HexFace, HexHit, HoveredHex, Palette, TileEvent};
+
+/// How far a stick has to be pushed before it moves the cursor.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Cosine of the widest angle between the pushed direction and a neighbor the cursor moves to.
+const MIN_ALIGNMENT: f32 = 0.5;
+
+/// A hex selected with the keyboard or a gamepad instead of the mouse. While it is active it
+/// drives `HoveredHex`; moving the mouse hands control back.
+#[derive(Resource, Default)]
+pub struct HexCursor {
+    pub hex: Option<Hex>,
+    pub active: bool,
+    repeat: Timer,
+}
+
+pub fn hex_cursor_system(
+    time: Res<Time>,
+    actions: Res<Input<Action>>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    config: Res<GridConfig>,
+    tracker: Res<GridTracker>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut cursor: ResMut<HexCursor>,
+    mut hovered: ResMut<HoveredHex>,
+    mut events: EventWriter<TileEvent>,
+    mut erase_tile: EventWriter<EraseTile>,
+) {
+    if mouse_motion.iter().count() > 0 {
+        cursor.active = false;
+    }
+
+    let key_direction: Vec2 = (0..6)
+        .filter(|direction| actions.pressed(Action::MoveCursor(*direction)))
+        .map(|direction| Vec2::from_angle(direction as f32 * FRAC_PI_3))
+        .sum();
+    let stick: Vec2 = gamepads
+        .iter()
+        .map(|gamepad| {
+            let axis = |axis_type| {
+                axes.get(GamepadAxis::new(gamepad, axis_type))
+                    .unwrap_or_default()
+            };
+            Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                axis(GamepadAxisType::LeftStickY),
+            )
+        })
+        .find(|stick| stick.length() > STICK_THRESHOLD)
+        .unwrap_or_default();
+    let direction = if key_direction != Vec2::ZERO {
+        key_direction
+    } else {
+        stick
+    };
+
+    let pressed_now = (0..6).any(|direction| actions.just_pressed(Action::MoveCursor(direction)));
+    cursor.repeat.tick(time.delta());
+    if direction == Vec2::ZERO {
+        cursor.repeat = Timer::from_seconds(0.0, TimerMode::Once);
+    } else if pressed_now || cursor.repeat.finished() {
+        cursor.repeat = Timer::from_seconds(config.cursor_repeat, TimerMode::Once);
+        let start = cursor
+            .hex
+            .filter(|_| cursor.active)
+            .or(hovered.0.map(|hit| hit.hex))
+            .or(cursor.hex)
+            .unwrap_or(Hex::ZERO);
+        let screen_to_world = cameras
+            .iter()
+            .find(|(camera, _)| camera.is_active)
+            .map(|(_, transform)| transform.right())
+            .map_or(Vec2::X, |right| {
+                Vec2::new(right.x, right.z).normalize_or_zero()
+            });
+        // The screen's up is the camera's right turned a quarter towards the far side.
+        let world = screen_to_world * direction.x - screen_to_world.perp() * direction.y;
+        let next = step(&tracker, start, world);
+        cursor.hex = Some(next);
+        cursor.active = true;
+    }
+
+    if !cursor.active {
+        return;
+    }
+    let Some(hex) = cursor.hex else {
+        return;
+    };
+    let hit = tracker.height(hex).map(|height| {
+        let position = tracker.layout.hex_to_world_pos(hex);
+        HexHit {
+            hex,
+            layer: height.saturating_sub(1),
+            face: if height == 0 {
+                HexFace::Ground
+            } else {
+                HexFace::Top
+            },
+            position: Vec3::new(position.x, height as f32 * config.layer_height, position.y),
+        }
+    });
+    if hovered.0.map(|hit| hit.hex) != hit.map(|hit| hit.hex) {
+        if let Some(previous) = hovered.0 {
+            events.send(TileEvent::Out(previous));
+        }
+        if let Some(hit) = hit {
+            events.send(TileEvent::Over(hit));
+        }
+    }
+    hovered.0 = hit;
+
+    if let Some(hit) = hit {
+        if actions.just_pressed(Action::Place) {
+            events.send(TileEvent::Down(hit));
+            events.send(TileEvent::Up(hit));
+        }
+        if actions.just_pressed(Action::Erase) {
+            erase_tile.send(EraseTile { hex: hit.hex });
+        }
+    }
+}
+
+/// Steps through the palette with `NextVariant` and `PreviousVariant`, or jumps to an entry with
+/// `SelectVariant`.
+pub fn select_variant_system(
+    actions: Res<Input<Action>>,
+    palette: Res<Palette>,
+    mut tracker: ResMut<GridTracker>,
+) {
+    let variants = &palette.0;
+    if variants.is_empty() {
+        return;
+    }
+    let current = variants
+        .iter()
+        .position(|variant| *variant == tracker.current_tile_variant)
+        .unwrap_or_default() as i32;
+    let cycle = actions.just_pressed(Action::NextVariant) as i32
+        - actions.just_pressed(Action::PreviousVariant) as i32;
+    let selected = (1..=9)
+        .find(|number| actions.just_pressed(Action::SelectVariant(*number)))
+        .map(|number| number as i32 - 1)
+        .filter(|index| (*index as usize) < variants.len());
+    let next = match selected {
+        Some(index) => index,
+        None if cycle != 0 => (current + cycle).rem_euclid(variants.len() as i32),
+        None => return,
+    };
+    tracker.current_tile_variant = variants[next as usize].clone();
+}
+
+/// The board neighbor of `hex` that lies closest to the world direction `towards`, or `hex` if
+/// none lies within 60° of it, e.g. at the edge of a bounded board.
+fn step(tracker: &GridTracker, hex: Hex, towards: Vec2) -> Hex {
+    let center = tracker.layout.hex_to_world_pos(hex);
+    let towards = towards.normalize_or_zero();
+    hex.all_neighbors()
+        .into_iter()
+        .filter(|neighbor| tracker.height(*neighbor).is_some())
+        .map(|neighbor| {
+            let offset = tracker.layout.hex_to_world_pos(neighbor) - center;
+            (neighbor, offset.normalize().dot(towards))
+        })
+        .filter(|(_, alignment)| *alignment > MIN_ALIGNMENT)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(hex, |(neighbor, _)| neighbor)
+}
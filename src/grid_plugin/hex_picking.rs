@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+use hexx::{Direction, Hex, HexLayout};
+
+use crate::bindings_plugin::Action;
+
+use super::{GridConfig, GridTracker, HexCursor, Tile, TileEvent};
+
+/// Which part of a column a ray hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexFace {
+    /// The base plate of an empty hex.
+    Ground,
+    /// The top of the highest tile in the column.
+    Top,
+    /// The side of a tile, facing the given neighbor.
+    Side(Direction),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexHit {
+    pub hex: Hex,
+    /// Layer of the tile that was hit, `0` for `HexFace::Ground`.
+    pub layer: u32,
+    pub face: HexFace,
+    pub position: Vec3,
+}
+
+/// The hex currently under the cursor, if any.
+#[derive(Resource, Default)]
+pub struct HoveredHex(pub Option<HexHit>);
+
+/// Intersects `ray` with the columns of the grid. `columns` holds the tiles on every hex of the
+/// board; hexes missing from it are treated as empty space without a base plate.
+///
+/// The ray is walked across the hex cells it passes over, in order, so the first column whose
+/// side or top it meets is the one returned and nothing behind it can be picked.
+pub fn cast_ray(
+    ray: Ray,
+    layout: &HexLayout,
+    columns: &HashMap<Hex, Vec<Tile>>,
+    layer_height: f32,
+) -> Option<HexHit> {
+    let max_height = columns.values().map(Vec::len).max().unwrap_or(0) as f32 * layer_height;
+    let mut t = 0.0;
+    if ray.origin.y > max_height {
+        if ray.direction.y >= 0.0 {
+            return None;
+        }
+        t = (max_height - ray.origin.y) / ray.direction.y;
+    }
+
+    let origin = Vec2::new(ray.origin.x, ray.origin.z);
+    let direction = Vec2::new(ray.direction.x, ray.direction.z);
+    let mut hex = layout.world_pos_to_hex(origin + direction * t);
+    let mut previous: Option<Hex> = None;
+
+    // A ray can only cross so many cells before it either leaves the board or reaches the ground.
+    let board_radius = columns.keys().map(|hex| hex.ulength()).max().unwrap_or(0);
+    let max_steps = 4 * (board_radius + hex.ulength() + 2) as usize;
+
+    for _ in 0..max_steps {
+        let (enter, exit, exit_edge) = cell_span(layout, hex, origin, direction);
+        let enter = enter.max(t);
+        let y_enter = ray.origin.y + ray.direction.y * enter;
+        let y_exit = ray.origin.y + ray.direction.y * exit;
+
+        if let Some(height) = columns.get(&hex).map(|column| column.len() as u32) {
+            let top = height as f32 * layer_height;
+            if y_enter <= top && height > 0 {
+                let layer = ((y_enter.max(0.0) / layer_height) as u32).min(height - 1);
+                let face = previous
+                    .and_then(|previous| hex.neighbor_direction(previous))
+                    .map_or(HexFace::Top, HexFace::Side);
+                return Some(HexHit {
+                    hex,
+                    layer,
+                    face,
+                    position: ray.get_point(enter),
+                });
+            }
+            if y_exit <= top {
+                let t_top = (top - ray.origin.y) / ray.direction.y;
+                let (layer, face) = if height == 0 {
+                    (0, HexFace::Ground)
+                } else {
+                    (height - 1, HexFace::Top)
+                };
+                return Some(HexHit {
+                    hex,
+                    layer,
+                    face,
+                    position: ray.get_point(t_top),
+                });
+            }
+        } else if y_exit <= 0.0 {
+            return None;
+        }
+
+        let exit_edge = exit_edge?;
+        let corners = layout.hex_corners(hex);
+        let center = layout.hex_to_world_pos(hex);
+        let edge_middle = (corners[exit_edge] + corners[(exit_edge + 1) % 6]) / 2.0;
+        previous = Some(hex);
+        hex = layout.world_pos_to_hex(center + (edge_middle - center) * 2.0);
+        t = exit;
+    }
+    None
+}
+
+/// Parametric range over which the 2D ray `origin + t * direction` lies within the cell of
+/// `hex`, along with the edge it leaves through.
+fn cell_span(
+    layout: &HexLayout,
+    hex: Hex,
+    origin: Vec2,
+    direction: Vec2,
+) -> (f32, f32, Option<usize>) {
+    let corners = layout.hex_corners(hex);
+    let center = layout.hex_to_world_pos(hex);
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut exit_edge = None;
+    for (i, &a) in corners.iter().enumerate() {
+        let b = corners[(i + 1) % 6];
+        let mut normal = (b - a).perp();
+        if normal.dot(center - a) > 0.0 {
+            normal = -normal;
+        }
+        let distance = normal.dot(origin - a);
+        let speed = normal.dot(direction);
+        if speed.abs() < f32::EPSILON {
+            continue;
+        }
+        let t = -distance / speed;
+        if speed < 0.0 {
+            enter = enter.max(t);
+        } else if t < exit {
+            exit = t;
+            exit_edge = Some(i);
+        }
+    }
+    (enter, exit, exit_edge)
+}
+
+/// Casts a ray from `position`, in window coordinates with the origin at the bottom left, through
+/// the active camera onto the board.
+pub(super) fn pick(
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+    tracker: &GridTracker,
+    config: &GridConfig,
+    position: Vec2,
+) -> Option<HexHit> {
+    let (camera, transform) = cameras.iter().find(|(camera, _)| camera.is_active)?;
+    let ray = camera.viewport_to_world(transform, position)?;
+    cast_ray(ray, &tracker.layout, &tracker.columns, config.layer_height)
+}
+
+pub fn hex_picking_system(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    ui_interactions: Query<&Interaction, With<Node>>,
+    tracker: Res<GridTracker>,
+    config: Res<GridConfig>,
+    actions: Res<Input<Action>>,
+    touches: Res<Touches>,
+    cursor: Res<HexCursor>,
+    mut held: Local<Option<HexHit>>,
+    mut hovered: ResMut<HoveredHex>,
+    mut events: EventWriter<TileEvent>,
+) {
+    // Touches are picked by `touch_picking_system`, and the hex cursor drives the hover itself.
+    if touches.iter().next().is_some() || touches.any_just_released() || cursor.active {
+        return;
+    }
+    let over_ui = ui_interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    let hit = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .filter(|_| !over_ui)
+        .and_then(|cursor| pick(&cameras, &tracker, &config, cursor));
+
+    let previous = hovered.0;
+    if previous.map(|hit| hit.hex) != hit.map(|hit| hit.hex) {
+        if let Some(previous) = previous {
+            events.send(TileEvent::Out(previous));
+        }
+        if let Some(hit) = hit {
+            events.send(TileEvent::Over(hit));
+        }
+    }
+    if let Some(hit) = hit {
+        if actions.just_pressed(Action::Place) {
+            events.send(TileEvent::Down(hit));
+            *held = Some(hit);
+        } else if held.is_some() {
+            *held = Some(hit);
+        }
+    }
+    if actions.just_released(Action::Place) {
+        if let Some(last) = held.take() {
+            events.send(TileEvent::Up(last));
+        }
+    }
+    hovered.0 = hit;
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::bindings_plugin::Action;
+
+use super::tile_events::{place_tile, recolor_tile, remove_top_tile};
+use super::{
+    GridConfig, GridTracker, MapLoaded, TilePlaced, TileRecolored, TileRemoved, TileVariant,
+};
+
+/// A single change to the map that can be undone.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    Placed {
+        hex: Hex,
+        variant: TileVariant,
+    },
+    Removed {
+        hex: Hex,
+        variant: TileVariant,
+    },
+    Recolored {
+        hex: Hex,
+        layer: u32,
+        previous_variant: TileVariant,
+    },
+}
+
+impl From<&TilePlaced> for Edit {
+    fn from(event: &TilePlaced) -> Self {
+        Edit::Placed {
+            hex: event.hex,
+            variant: event.variant.clone(),
+        }
+    }
+}
+
+impl From<&TileRemoved> for Edit {
+    fn from(event: &TileRemoved) -> Self {
+        Edit::Removed {
+            hex: event.hex,
+            variant: event.variant.clone(),
+        }
+    }
+}
+
+impl From<&TileRecolored> for Edit {
+    fn from(event: &TileRecolored) -> Self {
+        Edit::Recolored {
+            hex: event.hex,
+            layer: event.layer,
+            previous_variant: event.previous_variant.clone(),
+        }
+    }
+}
+
+/// Edits made since the map was set up or loaded, oldest first. The edits of one frame, such as
+/// a whole fill, form a single step and are undone together.
+#[derive(Resource, Default)]
+pub struct EditHistory {
+    pub steps: Vec<Vec<Edit>>,
+    /// Edits of the current frame in the order they were made, logged by whatever made them.
+    /// Kept apart from the map events, whose kinds can't be put back in order once read.
+    pending: Vec<Edit>,
+}
+
+impl EditHistory {
+    pub(super) fn log(&mut self, edit: impl Into<Edit>) {
+        self.pending.push(edit.into());
+    }
+}
+
+/// Closes the step of the current frame.
+pub fn record_edits(
+    config: Res<GridConfig>,
+    mut history: ResMut<EditHistory>,
+    mut loaded: EventReader<MapLoaded>,
+) {
+    if loaded.iter().count() > 0 {
+        *history = EditHistory::default();
+        return;
+    }
+    if history.pending.is_empty() {
+        return;
+    }
+    let step = std::mem::take(&mut history.pending);
+    history.steps.push(step);
+    let excess = history.steps.len().saturating_sub(config.undo_limit);
+    history.steps.drain(..excess);
+}
+
+/// Reverts the most recent step when `Undo` is pressed.
+pub fn undo_system(
+    mut commands: Commands,
+    actions: Res<Input<Action>>,
+    mut history: ResMut<EditHistory>,
+    mut tracker: ResMut<GridTracker>,
+    mut tile_placed: EventWriter<TilePlaced>,
+    mut tile_removed: EventWriter<TileRemoved>,
+    mut tile_recolored: EventWriter<TileRecolored>,
+) {
+    if !actions.just_pressed(Action::Undo) {
+        return;
+    }
+    let Some(step) = history.steps.pop() else {
+        return;
+    };
+    for edit in step.into_iter().rev() {
+        match edit {
+            Edit::Placed { hex, .. } => {
+                if let Some(removed) = remove_top_tile(&mut commands, &mut tracker, hex) {
+                    tile_removed.send(removed);
+                }
+            }
+            Edit::Removed { hex, variant } => {
+                if tracker.height(hex).is_some() {
+                    let placed = place_tile(&mut commands, &mut tracker, hex, variant);
+                    tile_placed.send(placed);
+                }
+            }
+            Edit::Recolored {
+                hex,
+                layer,
+                previous_variant,
+            } => {
+                let recolored =
+                    recolor_tile(&mut commands, &mut tracker, hex, layer, previous_variant);
+                if let Some(recolored) = recolored {
+                    tile_recolored.send(recolored);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use hexx::Hex;
+
+use super::TileVariant;
+
+/// Sent after a tile has been added on top of a column.
+#[derive(Clone, Debug)]
+pub struct TilePlaced {
+    pub hex: Hex,
+    pub layer: u32,
+    pub variant: TileVariant,
+    pub entity: Entity,
+}
+
+/// Sent after a tile has been taken off the map. The entity is already despawned.
+#[derive(Clone, Debug)]
+pub struct TileRemoved {
+    pub hex: Hex,
+    pub layer: u32,
+    pub variant: TileVariant,
+    pub entity: Entity,
+}
+
+/// Sent after an existing tile has changed variant.
+#[derive(Clone, Debug)]
+pub struct TileRecolored {
+    pub hex: Hex,
+    pub layer: u32,
+    pub variant: TileVariant,
+    pub previous_variant: TileVariant,
+    pub entity: Entity,
+}
+
+/// Sent once the whole map has been (re)built, instead of one `TilePlaced` per tile.
+#[derive(Clone, Debug)]
+pub struct MapLoaded {
+    pub tile_count: usize,
+}
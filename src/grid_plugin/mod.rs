@@ -1,30 +1,121 @@
+mod chunks;
+mod editor_tool;
+mod hex_cursor;
+mod hex_picking;
+mod history;
+mod map_events;
 mod setup;
 mod tile_events;
 mod tile_variant;
+mod tools;
+mod touch;
 
 use bevy::prelude::*;
 
 use crate::GameState;
 
+pub use self::chunks::{chunk_hexes, chunk_of, ChunkMesh, ChunkMeshBuilder, ChunkTracker};
+use self::chunks::{mark_dirty_chunks, rebuild_chunks};
+pub use self::editor_tool::{EditorTool, EditorToolAppExt, EditorTools, MapAccess};
+pub use self::hex_cursor::HexCursor;
+use self::hex_cursor::{hex_cursor_system, select_variant_system};
+use self::hex_picking::hex_picking_system;
+pub use self::hex_picking::{cast_ray, HexFace, HexHit, HoveredHex};
+use self::history::{record_edits, undo_system};
+pub use self::history::{Edit, EditHistory};
+pub use self::map_events::{MapLoaded, TilePlaced, TileRecolored, TileRemoved};
 use self::setup::setup;
-pub use self::setup::{GridTracker, HexCoords, TileSelector};
-use self::tile_events::{handle_spawn_tile, handle_update_parent, TileEvent};
-pub use self::tile_variant::TileVariant;
+pub use self::setup::{GridTracker, HexCoords, HexHighlight, Selection, Tile};
+use self::tile_events::{grow_board, handle_erase_tile, update_highlight};
+pub use self::tile_events::{EraseTile, TileEvent};
+pub use self::tile_variant::{Palette, TileVariant};
+use self::tools::{handle_tool_events, select_tool_system};
+pub use self::tools::{ActiveTool, Tool};
+use self::touch::touch_picking_system;
 
-pub static GRID_RADIUS: u32 = 4;
-pub static GRID_MARGIN: f32 = 0.05;
+#[derive(Resource, Clone, Debug)]
+pub struct GridConfig {
+    /// Number of rings of selectable hexes around the center.
+    pub radius: u32,
+    /// World height of a single tile.
+    pub layer_height: f32,
+    /// Gap left between neighboring tiles, as a fraction of a tile's width.
+    pub margin: f32,
+    /// Width of the square of axial coordinates whose tiles are merged into one mesh.
+    pub chunk_size: u32,
+    /// Start from `radius` but add the neighbors of every placed tile to the board, so it grows
+    /// without limit as you build past its edge.
+    pub unbounded: bool,
+    /// Seconds a finger has to rest on a hex before it counts as a long press.
+    pub long_press_time: f32,
+    /// Seconds between cursor steps while a direction is held.
+    pub cursor_repeat: f32,
+    /// Number of steps kept for undo.
+    pub undo_limit: usize,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            radius: 4,
+            layer_height: 0.5,
+            margin: 0.05,
+            chunk_size: 8,
+            unbounded: false,
+            long_press_time: 0.5,
+            cursor_repeat: 0.18,
+            undo_limit: 1000,
+        }
+    }
+}
 
-pub struct GridPlugin;
+#[derive(Default)]
+pub struct GridPlugin {
+    pub config: GridConfig,
+}
 
 impl Plugin for GridPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<TileEvent>()
-            .add_system(setup.in_schedule(OnEnter(GameState::Playing)))
+        // Tools added before the plugin keep their numbers but come after the built-in ones.
+        let mut tools = EditorTools::built_in();
+        if let Some(custom) = app.world.remove_resource::<EditorTools>() {
+            tools.append(custom);
+        }
+
+        app.insert_resource(self.config.clone())
+            .insert_resource(tools)
+            .add_event::<TileEvent>()
+            .add_event::<EraseTile>()
+            .add_event::<TilePlaced>()
+            .add_event::<TileRemoved>()
+            .add_event::<TileRecolored>()
+            .add_event::<MapLoaded>()
+            .init_resource::<HoveredHex>()
+            .init_resource::<ChunkTracker>()
+            .init_resource::<Selection>()
+            .init_resource::<HexCursor>()
+            .init_resource::<Palette>()
+            .init_resource::<ActiveTool>()
+            .init_resource::<EditHistory>()
+            .add_system(setup.in_schedule(OnExit(GameState::Loading)))
             .add_systems(
-                (handle_update_parent, handle_spawn_tile)
+                (
+                    select_variant_system,
+                    select_tool_system,
+                    hex_cursor_system,
+                    hex_picking_system,
+                    touch_picking_system,
+                    handle_tool_events.run_if(on_event::<TileEvent>()),
+                    handle_erase_tile,
+                    record_edits,
+                    undo_system,
+                    grow_board.run_if(|config: Res<GridConfig>| config.unbounded),
+                    update_highlight,
+                    mark_dirty_chunks,
+                    rebuild_chunks,
+                )
                     .chain()
-                    .in_set(OnUpdate(GameState::Playing))
-                    .distributive_run_if(on_event::<TileEvent>()),
+                    .in_set(OnUpdate(GameState::Playing)),
             );
     }
 }
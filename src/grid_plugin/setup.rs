@@ -1,15 +1,15 @@
 use bevy::prelude::*;
 use bevy::render::mesh::Indices;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 use bevy::{pbr::NotShadowCaster, render::render_resource::PrimitiveTopology};
-use bevy_mod_picking::prelude::*;
 use derive_more::Constructor;
 use hexx::*;
 
-use super::{tile_events::TileEvent, TileVariant, GRID_MARGIN, GRID_RADIUS};
+use super::{chunk_of, ChunkTracker, GridConfig, MapLoaded, TileVariant};
 
+/// Marks the translucent preview tile shown on top of the hovered column.
 #[derive(Component)]
-pub struct TileSelector;
+pub struct HexHighlight;
 
 #[derive(Clone, Constructor, Default)]
 pub struct HexCoords {
@@ -17,98 +17,142 @@ pub struct HexCoords {
     pub layer: u32,
 }
 
+#[derive(Clone, Debug)]
+pub struct Tile {
+    pub entity: Entity,
+    pub variant: TileVariant,
+}
+
+/// Hexes selected in the editor.
+#[derive(Resource, Default)]
+pub struct Selection(pub HashSet<Hex>);
+
 #[derive(Resource, Default)]
 pub struct GridTracker {
     pub current_tile_variant: TileVariant,
     pub layout: HexLayout,
+    /// Placed tiles.
     pub tiles: HashMap<Entity, HexCoords>,
+    /// Every hex of the board, with the tiles stacked on it from the bottom up.
+    pub columns: HashMap<Hex, Vec<Tile>>,
     pub tile_materials: HashMap<TileVariant, Handle<StandardMaterial>>,
-    pub hidden_material_handle: Handle<StandardMaterial>,
     pub mesh_handle: Handle<Mesh>,
     pub last_hex: Hex,
-    pub drag_layer: u32,
+    /// Height of the column a drag started on, while the pointer is held down.
+    pub drag_layer: Option<u32>,
+}
+
+impl GridTracker {
+    /// Number of tiles on `hex`, or `None` if it is not part of the board.
+    pub fn height(&self, hex: Hex) -> Option<u32> {
+        self.columns.get(&hex).map(|column| column.len() as u32)
+    }
+
+    /// The material tiles of `variant` are drawn with, made the first time it is asked for.
+    pub fn tile_material(
+        &mut self,
+        variant: &TileVariant,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        self.tile_materials
+            .entry(variant.clone())
+            .or_insert_with(|| materials.add(StandardMaterial::from(variant.clone())))
+            .clone()
+    }
+
+    /// Average world position of the board hexes.
+    pub fn board_center(&self) -> Vec2 {
+        if self.columns.is_empty() {
+            return Vec2::ZERO;
+        }
+        let sum: Vec2 = self
+            .columns
+            .keys()
+            .map(|hex| self.layout.hex_to_world_pos(*hex))
+            .sum();
+        sum / self.columns.len() as f32
+    }
+
+    /// World-space bounding box of the given board hexes and the tiles stacked on them, or `None`
+    /// if none of them are on the board.
+    pub fn bounds(
+        &self,
+        hexes: impl IntoIterator<Item = Hex>,
+        layer_height: f32,
+    ) -> Option<(Vec3, Vec3)> {
+        let radius = self.layout.hex_size.max_element();
+        hexes
+            .into_iter()
+            .filter_map(|hex| Some((hex, self.height(hex)?)))
+            .map(|(hex, height)| {
+                let center = self.layout.hex_to_world_pos(hex);
+                (
+                    Vec3::new(center.x - radius, 0.0, center.y - radius),
+                    Vec3::new(
+                        center.x + radius,
+                        height as f32 * layer_height,
+                        center.y + radius,
+                    ),
+                )
+            })
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    }
 }
 
 pub fn setup(
     mut commands: Commands,
+    config: Res<GridConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut chunks: ResMut<ChunkTracker>,
+    mut map_loaded: EventWriter<MapLoaded>,
 ) {
     let layout = HexLayout {
         orientation: HexOrientation::Pointy,
         ..default()
     };
 
-    let mesh_handle = meshes.add(compute_mesh(ColumnMeshBuilder::new(&layout, 0.5).build()));
+    let mesh_handle = meshes.add(compute_mesh(
+        ColumnMeshBuilder::new(&layout, config.layer_height).build(),
+    ));
     let empty_tile_material_handle = materials.add(Color::GRAY.with_a(0.5).into());
-    let hidden_material_handle = materials.add(Color::RED.with_a(0.0).into());
     let selector_material_handle = materials.add(Color::rgb(0.66, 0.66, 0.66).with_a(0.3).into());
 
     let tile_materials = TileVariant::initialize_materials(&mut materials);
 
-    let tiles =
-        shapes::hexagon(Hex::ZERO, GRID_RADIUS)
-            .map(|hex| {
-                let position = layout.hex_to_world_pos(hex);
-                commands.spawn(PbrBundle {
-                    transform: Transform::from_xyz(position.x, 0.0, position.y)
-                        .with_scale(Vec3::new(1.0 - GRID_MARGIN, 0.1, 1.0 - GRID_MARGIN)),
-                    mesh: mesh_handle.clone(),
-                    material: empty_tile_material_handle.clone(),
-                    ..default()
-                });
-                let entity = commands
-                    .spawn((
-                        PbrBundle {
-                            transform: Transform::from_xyz(position.x, 0.0, position.y).with_scale(
-                                Vec3::new(1.0 - GRID_MARGIN, 1.0 - GRID_MARGIN, 1.0 - GRID_MARGIN),
-                            ),
-                            mesh: mesh_handle.clone(),
-                            material: hidden_material_handle.clone(),
-                            ..default()
-                        },
-                        NotShadowCaster,
-                        OnPointer::<Over>::send_event::<TileEvent>(),
-                        OnPointer::<Out>::send_event::<TileEvent>(),
-                        OnPointer::<Down>::send_event::<TileEvent>(),
-                        OnPointer::<Up>::send_event::<TileEvent>(),
-                        PickHighlight,
-                        Highlight {
-                            hovered: Some(HighlightKind::Fixed(selector_material_handle.clone())),
-                            pressed: Some(HighlightKind::Fixed(hidden_material_handle.clone())),
-                        },
-                        TileSelector,
-                    ))
-                    .with_children(|commands| {
-                        commands.spawn((
-                            PbrBundle {
-                                transform: Transform {
-                                    scale: Vec3::new(1.0, 0.01, 1.0),
-                                    ..default()
-                                },
-                                mesh: mesh_handle.clone(),
-                                material: hidden_material_handle.clone(),
-                                ..default()
-                            },
-                            RaycastPickTarget::default(),
-                            Interaction::None,
-                        ));
-                    })
-                    .id();
-                (entity, HexCoords::new(hex, 0))
-            })
-            .collect();
+    let columns: HashMap<Hex, Vec<Tile>> = shapes::hexagon(Hex::ZERO, config.radius)
+        .map(|hex| (hex, Vec::new()))
+        .collect();
+    chunks.plate_material = empty_tile_material_handle;
+    // The board is built when editing starts, which may be long after `MapLoaded` was read.
+    chunks
+        .dirty
+        .extend(columns.keys().map(|hex| chunk_of(*hex, config.chunk_size)));
+
+    commands.spawn((
+        PbrBundle {
+            transform: Transform::from_scale(Vec3::splat(1.0 - config.margin)),
+            mesh: mesh_handle.clone(),
+            material: selector_material_handle,
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        NotShadowCaster,
+        HexHighlight,
+    ));
 
     commands.insert_resource(GridTracker {
         current_tile_variant: TileVariant::Purple,
         layout,
-        tiles,
+        tiles: HashMap::new(),
+        columns,
         tile_materials,
-        hidden_material_handle,
         mesh_handle,
         last_hex: Hex::ZERO,
-        drag_layer: 0,
+        drag_layer: None,
     });
+
+    map_loaded.send(MapLoaded { tile_count: 0 });
 }
 
 fn compute_mesh(mesh_info: MeshInfo) -> Mesh {
@@ -1,143 +1,167 @@
 use bevy::prelude::*;
-use bevy_mod_picking::prelude::*;
+use hexx::Hex;
 
-use crate::grid_plugin::{HexCoords, TileSelector, GRID_MARGIN};
+use crate::grid_plugin::{
+    chunk_of, ChunkTracker, EditHistory, GridConfig, HexCoords, HexHighlight, HexHit, HoveredHex,
+    MapLoaded, Tile, TilePlaced, TileRecolored, TileRemoved, TileVariant,
+};
 
 use super::GridTracker;
 
+/// Pointer events on the board, from the mouse or a touch screen. Every `Down` is followed by an
+/// `Up`, at the last hex hit if the pointer has left the board.
+#[derive(Clone, Debug)]
 pub enum TileEvent {
-    Over(ListenedEvent<Over>),
-    Out(ListenedEvent<Out>),
-    Down(ListenedEvent<Down>),
-    Up(ListenedEvent<Up>),
+    Over(HexHit),
+    Out(HexHit),
+    Down(HexHit),
+    Up(HexHit),
+    /// The pointer was held on one hex without moving, e.g. a long press on a touch screen.
+    LongPress(HexHit),
 }
 
-impl From<ListenedEvent<Over>> for TileEvent {
-    fn from(event: ListenedEvent<Over>) -> Self {
-        TileEvent::Over(event)
-    }
+/// Takes the top tile off the column at `hex` without going through a pointer, e.g. from the
+/// `Erase` action on the hex cursor.
+#[derive(Clone, Debug)]
+pub struct EraseTile {
+    pub hex: Hex,
 }
-impl From<ListenedEvent<Out>> for TileEvent {
-    fn from(event: ListenedEvent<Out>) -> Self {
-        TileEvent::Out(event)
+
+/// Stacks a new tile of `variant` on top of the column at `hex`, which must be on the board.
+pub(super) fn place_tile(
+    commands: &mut Commands,
+    tracker: &mut GridTracker,
+    hex: Hex,
+    variant: TileVariant,
+) -> TilePlaced {
+    let layer = tracker.height(hex).unwrap();
+    let entity = commands.spawn(variant.clone()).id();
+    tracker.tiles.insert(entity, HexCoords::new(hex, layer));
+    tracker.columns.get_mut(&hex).unwrap().push(Tile {
+        entity,
+        variant: variant.clone(),
+    });
+    TilePlaced {
+        hex,
+        layer,
+        variant,
+        entity,
     }
 }
-impl From<ListenedEvent<Down>> for TileEvent {
-    fn from(event: ListenedEvent<Down>) -> Self {
-        TileEvent::Down(event)
-    }
+
+/// Takes the top tile off the column at `hex`, if it has any.
+pub(super) fn remove_top_tile(
+    commands: &mut Commands,
+    tracker: &mut GridTracker,
+    hex: Hex,
+) -> Option<TileRemoved> {
+    let tile = tracker.columns.get_mut(&hex)?.pop()?;
+    let layer = tracker.height(hex).unwrap_or_default();
+    tracker.tiles.remove(&tile.entity);
+    commands.entity(tile.entity).despawn();
+    Some(TileRemoved {
+        hex,
+        layer,
+        variant: tile.variant,
+        entity: tile.entity,
+    })
 }
-impl From<ListenedEvent<Up>> for TileEvent {
-    fn from(event: ListenedEvent<Up>) -> Self {
-        TileEvent::Up(event)
+
+/// Changes the tile at `layer` of the column at `hex` to `variant`. Returns `None` if there is
+/// no such tile or it already has that variant.
+pub(super) fn recolor_tile(
+    commands: &mut Commands,
+    tracker: &mut GridTracker,
+    hex: Hex,
+    layer: u32,
+    variant: TileVariant,
+) -> Option<TileRecolored> {
+    let tile = tracker.columns.get_mut(&hex)?.get_mut(layer as usize)?;
+    if tile.variant == variant {
+        return None;
     }
+    let previous_variant = std::mem::replace(&mut tile.variant, variant.clone());
+    commands.entity(tile.entity).insert(variant.clone());
+    Some(TileRecolored {
+        hex,
+        layer,
+        variant,
+        previous_variant,
+        entity: tile.entity,
+    })
 }
 
-pub fn handle_update_parent(
+/// Removes the top tile of a column that is long-pressed or erased, whichever tool is active.
+pub fn handle_erase_tile(
     mut commands: Commands,
     mut events: EventReader<TileEvent>,
-    mouse: Res<Input<MouseButton>>,
-    q_interaction: Query<&Interaction>,
+    mut erase_tile: EventReader<EraseTile>,
+    mut tracker: ResMut<GridTracker>,
+    mut history: ResMut<EditHistory>,
+    mut tile_removed: EventWriter<TileRemoved>,
 ) {
-    fn update_parent_interaction<T: IsPointerEvent>(
-        commands: &mut Commands,
-        q_interaction: &Query<&Interaction>,
-        event: &ListenedEvent<T>,
-    ) {
-        if let Ok(interaction) = q_interaction.get(event.target) {
-            commands.entity(event.listener).insert(*interaction);
+    let long_pressed = events.iter().filter_map(|event| match event {
+        TileEvent::LongPress(hit) => Some(hit.hex),
+        _ => None,
+    });
+    let hexes: Vec<Hex> = long_pressed
+        .chain(erase_tile.iter().map(|event| event.hex))
+        .collect();
+    for hex in hexes {
+        if let Some(removed) = remove_top_tile(&mut commands, &mut tracker, hex) {
+            history.log(&removed);
+            tile_removed.send(removed);
         }
     }
+}
 
-    for event in events.iter() {
-        match event {
-            TileEvent::Over(event) => {
-                if !mouse.pressed(MouseButton::Left) {
-                    update_parent_interaction(&mut commands, &q_interaction, event);
-                }
-            }
-            TileEvent::Out(event) => {
-                if !mouse.pressed(MouseButton::Left) {
-                    update_parent_interaction(&mut commands, &q_interaction, event);
-                }
-            }
-            TileEvent::Down(event) => {
-                update_parent_interaction(&mut commands, &q_interaction, event);
-            }
-            TileEvent::Up(event) => {
-                update_parent_interaction(&mut commands, &q_interaction, event);
+/// Keeps a ring of empty columns around every tile of an unbounded board, including the tiles
+/// of a map that was just loaded.
+pub fn grow_board(
+    mut tracker: ResMut<GridTracker>,
+    config: Res<GridConfig>,
+    mut chunks: ResMut<ChunkTracker>,
+    mut tile_placed: EventReader<TilePlaced>,
+    mut map_loaded: EventReader<MapLoaded>,
+) {
+    let mut tiled: Vec<Hex> = tile_placed.iter().map(|event| event.hex).collect();
+    if map_loaded.iter().count() > 0 {
+        tiled.extend(
+            tracker
+                .columns
+                .iter()
+                .filter(|(_, column)| !column.is_empty())
+                .map(|(hex, _)| *hex),
+        );
+    }
+    for hex in tiled.into_iter().flat_map(|hex| hex.all_neighbors()) {
+        if !tracker.columns.contains_key(&hex) {
+            tracker.columns.insert(hex, Vec::new());
+            chunks.dirty.insert(chunk_of(hex, config.chunk_size));
+            for neighbor in hex.all_neighbors() {
+                chunks.dirty.insert(chunk_of(neighbor, config.chunk_size));
             }
         }
     }
 }
 
-pub fn handle_spawn_tile(
-    mut commands: Commands,
-    mut events: EventReader<TileEvent>,
-    mut tracker: ResMut<GridTracker>,
-    mouse: Res<Input<MouseButton>>,
-    mut q_transforms: Query<&mut Transform, With<TileSelector>>,
+pub fn update_highlight(
+    hovered: Res<HoveredHex>,
+    tracker: Res<GridTracker>,
+    config: Res<GridConfig>,
+    mut q_highlight: Query<(&mut Transform, &mut Visibility), With<HexHighlight>>,
 ) {
-    fn spawn_tile<T: IsPointerEvent>(
-        commands: &mut Commands,
-        tracker: &mut ResMut<GridTracker>,
-        q_transforms: &mut Query<&mut Transform, With<TileSelector>>,
-        event: &ListenedEvent<T>,
-    ) {
-        commands
-            .entity(event.listener)
-            .insert(tracker.hidden_material_handle.clone());
-        let mut transform = q_transforms.get_mut(event.listener).unwrap();
-        let hex_coords = tracker.tiles.get(&event.listener).unwrap().clone();
-        let position = tracker.layout.hex_to_world_pos(hex_coords.hex);
-        let entity = commands
-            .spawn(PbrBundle {
-                transform: Transform::from_xyz(
-                    position.x,
-                    (hex_coords.layer as f32) * 0.5,
-                    position.y,
-                )
-                .with_scale(Vec3::new(
-                    1.0 - GRID_MARGIN,
-                    1.0 - GRID_MARGIN,
-                    1.0 - GRID_MARGIN,
-                )),
-                mesh: tracker.mesh_handle.clone(),
-                material: tracker
-                    .tile_materials
-                    .get(&tracker.current_tile_variant)
-                    .unwrap()
-                    .clone(),
-                ..default()
-            })
-            .id();
-        transform.translation.y += 0.5;
-        tracker.last_hex = hex_coords.hex;
-        tracker.tiles.insert(entity, hex_coords);
-
-        let hex_coords = tracker.tiles.get_mut(&event.listener).unwrap();
-        hex_coords.layer += 1;
-    }
-
-    for event in events.iter() {
-        match event {
-            TileEvent::Down(event) => {
-                if let Some(HexCoords { layer, .. }) = tracker.tiles.get(&event.listener) {
-                    tracker.drag_layer = *layer;
-                    spawn_tile(&mut commands, &mut tracker, &mut q_transforms, event);
-                }
-            }
-            TileEvent::Over(event) => {
-                if let Some(HexCoords { hex, layer }) = tracker.tiles.get(&event.listener) {
-                    if mouse.pressed(MouseButton::Left)
-                        && *hex != tracker.last_hex
-                        && *layer <= tracker.drag_layer
-                    {
-                        spawn_tile(&mut commands, &mut tracker, &mut q_transforms, event);
-                    }
-                }
-            }
-            _ => {}
+    let Ok((mut transform, mut visibility)) = q_highlight.get_single_mut() else {
+        return;
+    };
+    match hovered.0 {
+        Some(hit) if tracker.drag_layer.is_none() => {
+            let position = tracker.layout.hex_to_world_pos(hit.hex);
+            let layer = tracker.height(hit.hex).unwrap_or_default();
+            transform.translation =
+                Vec3::new(position.x, layer as f32 * config.layer_height, position.y);
+            *visibility = Visibility::Inherited;
         }
+        _ => *visibility = Visibility::Hidden,
     }
 }
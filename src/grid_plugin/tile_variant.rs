@@ -1,22 +1,42 @@
+use std::fmt;
+
 use bevy::utils::HashMap;
 
 use bevy::prelude::*;
-use strum::EnumIter;
-use strum::IntoEnumIterator;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Eq, Hash, Default, EnumIter, Component)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default, Component, Serialize, Deserialize)]
 pub enum TileVariant {
     Cyan,
     #[default]
     Purple,
     Orange,
+    /// A color made at runtime, e.g. with the color picker. Maps store the color itself, so they
+    /// load without the palette that made it.
+    Custom {
+        r: u8,
+        g: u8,
+        b: u8,
+    },
 }
 
 impl TileVariant {
+    pub const BUILT_IN: [TileVariant; 3] =
+        [TileVariant::Cyan, TileVariant::Purple, TileVariant::Orange];
+
+    /// A custom variant of `color`, ignoring its alpha.
+    pub fn custom(color: Color) -> Self {
+        let [r, g, b, _] = color
+            .as_rgba_f32()
+            .map(|channel| (channel * 255.0).round() as u8);
+        TileVariant::Custom { r, g, b }
+    }
+
     pub fn initialize_materials(
         materials: &mut ResMut<Assets<StandardMaterial>>,
     ) -> HashMap<TileVariant, Handle<StandardMaterial>> {
-        Self::iter()
+        Self::BUILT_IN
+            .into_iter()
             .map(|tile_variant| {
                 (
                     tile_variant.clone(),
@@ -27,12 +47,50 @@ impl TileVariant {
     }
 }
 
+impl fmt::Display for TileVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileVariant::Custom { r, g, b } => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+            variant => write!(f, "{variant:?}"),
+        }
+    }
+}
+
+/// The variants offered for placement, in the order the palette lists them. Number keys select
+/// the first nine.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Palette(pub Vec<TileVariant>);
+
+impl Palette {
+    /// Most variants the palette row has room for.
+    pub const CAPACITY: usize = 13;
+
+    /// Appends `variant` unless it is listed already. Returns `false` if the palette is full.
+    pub fn add(&mut self, variant: TileVariant) -> bool {
+        if self.0.contains(&variant) {
+            return true;
+        }
+        if self.0.len() >= Self::CAPACITY {
+            return false;
+        }
+        self.0.push(variant);
+        true
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self(TileVariant::BUILT_IN.to_vec())
+    }
+}
+
 impl From<TileVariant> for Color {
     fn from(value: TileVariant) -> Self {
         match value {
             TileVariant::Cyan => Color::TEAL,
             TileVariant::Purple => Color::PURPLE,
             TileVariant::Orange => Color::ORANGE,
+            TileVariant::Custom { r, g, b } => Color::rgb_u8(r, g, b),
         }
     }
 }
@@ -0,0 +1,277 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use crate::bindings_plugin::Action;
+
+use super::editor_tool::{EditorTool, EditorTools, MapAccess};
+use super::{GridTracker, HexHit, TileEvent};
+
+/// What pressing and dragging on the board does.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub enum Tool {
+    /// Stack tiles; dragging builds across columns no taller than the first.
+    #[default]
+    Build,
+    /// Take off the top tile; dragging clears columns down to the first one's height.
+    Erase,
+    /// Recolor the top tile of every column the pointer passes.
+    Paint,
+    /// Recolor the connected area of top tiles with the same height and variant, or cover the
+    /// connected empty hexes.
+    Fill,
+    /// Select hexes; dragging adds to the selection.
+    Select,
+    /// Pick up the variant of a top tile, then go back to the previous tool.
+    Eyedropper,
+    /// A tool added with `add_editor_tool`, numbered in the order they were added.
+    Custom(u8),
+}
+
+impl Tool {
+    pub const BUILT_IN: [Tool; 6] = [
+        Tool::Build,
+        Tool::Erase,
+        Tool::Paint,
+        Tool::Fill,
+        Tool::Select,
+        Tool::Eyedropper,
+    ];
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tool::Custom(number) => write!(f, "Custom {number}"),
+            tool => write!(f, "{tool:?}"),
+        }
+    }
+}
+
+/// The tool pointer events are dispatched to.
+#[derive(Resource, Default)]
+pub struct ActiveTool {
+    pub tool: Tool,
+    previous: Tool,
+}
+
+impl ActiveTool {
+    pub fn set(&mut self, tool: Tool) {
+        if tool != self.tool {
+            self.previous = self.tool;
+            self.tool = tool;
+        }
+    }
+
+    /// Returns to the tool used before the current one.
+    pub fn restore(&mut self) {
+        self.set(self.previous);
+    }
+}
+
+impl EditorTools {
+    /// The built-in tools, in `Tool::BUILT_IN` order.
+    pub fn built_in() -> Self {
+        let mut tools = Self::default();
+        tools.insert(Tool::Build, BuildTool);
+        tools.insert(Tool::Erase, EraseTool);
+        tools.insert(Tool::Paint, PaintTool);
+        tools.insert(Tool::Fill, FillTool);
+        tools.insert(Tool::Select, SelectTool);
+        tools.insert(Tool::Eyedropper, EyedropperTool);
+        tools
+    }
+}
+
+pub fn select_tool_system(
+    actions: Res<Input<Action>>,
+    tools: Res<EditorTools>,
+    mut active: ResMut<ActiveTool>,
+) {
+    if let Some((tool, _)) = tools
+        .iter()
+        .find(|(tool, _)| actions.just_pressed(Action::UseTool(*tool)))
+    {
+        active.set(tool);
+    }
+}
+
+/// Feeds pointer events to the active tool: `Down` on a board hex presses, `Over` hovers or, while
+/// pressed, drags onto each further hex entered, and `Up` releases.
+pub fn handle_tool_events(
+    mut events: EventReader<TileEvent>,
+    mut tools: ResMut<EditorTools>,
+    mut map: MapAccess,
+) {
+    for event in events.iter() {
+        let tool = map.tool();
+        let Some(editor_tool) = tools.get_mut(tool) else {
+            continue;
+        };
+        match event {
+            TileEvent::Down(hit) => {
+                let Some(height) = map.height(hit.hex) else {
+                    continue;
+                };
+                let tracker = map.tracker_mut();
+                tracker.drag_layer = Some(height);
+                tracker.last_hex = hit.hex;
+                editor_tool.press(hit, &mut map);
+            }
+            TileEvent::Over(hit) if map.drag_layer().is_some() => {
+                if map.height(hit.hex).is_none() || map.tracker().last_hex == hit.hex {
+                    continue;
+                }
+                map.tracker_mut().last_hex = hit.hex;
+                editor_tool.drag(hit, &mut map);
+            }
+            TileEvent::Over(hit) => editor_tool.hover(hit, &mut map),
+            TileEvent::Up(hit) => {
+                if map.drag_layer().is_some() {
+                    editor_tool.release(hit, &mut map);
+                }
+                map.tracker_mut().drag_layer = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+struct BuildTool;
+
+impl EditorTool for BuildTool {
+    fn name(&self) -> &str {
+        "Build"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        map.place(hit.hex, map.current_variant().clone());
+    }
+
+    fn drag(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        if map.height(hit.hex) <= map.drag_layer() {
+            map.place(hit.hex, map.current_variant().clone());
+        }
+    }
+}
+
+struct EraseTool;
+
+impl EditorTool for EraseTool {
+    fn name(&self) -> &str {
+        "Erase"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        map.remove_top(hit.hex);
+    }
+
+    fn drag(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        if map.height(hit.hex) >= map.drag_layer() {
+            map.remove_top(hit.hex);
+        }
+    }
+}
+
+struct PaintTool;
+
+impl EditorTool for PaintTool {
+    fn name(&self) -> &str {
+        "Paint"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        if let Some(top) = map.height(hit.hex).and_then(|height| height.checked_sub(1)) {
+            map.recolor(hit.hex, top, map.current_variant().clone());
+        }
+    }
+
+    fn drag(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        self.press(hit, map);
+    }
+}
+
+struct FillTool;
+
+impl EditorTool for FillTool {
+    fn name(&self) -> &str {
+        "Fill"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        let Some(height) = map.height(hit.hex) else {
+            return;
+        };
+        let variant = map.current_variant().clone();
+        for hex in fill_area(map.tracker(), hit.hex) {
+            if height == 0 {
+                map.place(hex, variant.clone());
+            } else {
+                map.recolor(hex, height - 1, variant.clone());
+            }
+        }
+    }
+}
+
+struct SelectTool;
+
+impl EditorTool for SelectTool {
+    fn name(&self) -> &str {
+        "Select"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        let selection = &mut map.selection().0;
+        selection.clear();
+        selection.insert(hit.hex);
+    }
+
+    fn drag(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        map.selection().0.insert(hit.hex);
+    }
+}
+
+struct EyedropperTool;
+
+impl EditorTool for EyedropperTool {
+    fn name(&self) -> &str {
+        "Eyedropper"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        if let Some(variant) = map.top_variant(hit.hex).cloned() {
+            map.set_current_variant(variant);
+            map.active_tool().restore();
+        }
+    }
+}
+
+/// The board hexes connected to `start` whose columns have the same height and, if not empty,
+/// the same top variant.
+fn fill_area(tracker: &GridTracker, start: Hex) -> Vec<Hex> {
+    let key = |hex: Hex| {
+        let column = tracker.columns.get(&hex)?;
+        Some((column.len(), column.last().map(|tile| tile.variant.clone())))
+    };
+    let Some(start_key) = key(start) else {
+        return Vec::new();
+    };
+    let mut area = vec![start];
+    let mut visited = HashSet::from_iter([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(hex) = queue.pop_front() {
+        for neighbor in hex.all_neighbors() {
+            if key(neighbor).as_ref() == Some(&start_key) && visited.insert(neighbor) {
+                area.push(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    area
+}
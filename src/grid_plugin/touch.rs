@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use super::hex_picking::pick;
+use super::{GridConfig, GridTracker, HexHit, HoveredHex, TileEvent};
+
+/// A one-finger gesture, from the moment the finger touches the board until it lifts.
+#[derive(Default)]
+pub struct TouchGesture {
+    finger: Option<u64>,
+    start: Option<HexHit>,
+    started_at: f32,
+    /// The last hex the finger was over.
+    last: Option<HexHit>,
+    /// The finger left the hex it started on, so a `Down` was sent and tiles follow it.
+    dragging: bool,
+    /// The gesture became a long press or was taken over by a second finger; the rest of it is
+    /// ignored.
+    finished: bool,
+}
+
+/// Turns single-finger touches into the same `TileEvent`s as the mouse: a tap is a click, moving
+/// off the first hex drags, and holding still for `GridConfig::long_press_time` sends a
+/// `LongPress`. Gestures with more fingers, or starting on the interface, are left alone.
+pub fn touch_picking_system(
+    time: Res<Time>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    ui_interactions: Query<&Interaction, With<Node>>,
+    tracker: Res<GridTracker>,
+    config: Res<GridConfig>,
+    touches: Res<Touches>,
+    mut gesture: Local<TouchGesture>,
+    mut hovered: ResMut<HoveredHex>,
+    mut events: EventWriter<TileEvent>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    // Touches are measured from the top left, picking from the bottom left.
+    let hit_at = |position: Vec2| {
+        pick(
+            &cameras,
+            &tracker,
+            &config,
+            Vec2::new(position.x, window.height() - position.y),
+        )
+    };
+    let mut hover = |hit: Option<HexHit>, events: &mut EventWriter<TileEvent>| {
+        if hovered.0.map(|hit| hit.hex) != hit.map(|hit| hit.hex) {
+            if let Some(previous) = hovered.0 {
+                events.send(TileEvent::Out(previous));
+            }
+            if let Some(hit) = hit {
+                events.send(TileEvent::Over(hit));
+            }
+        }
+        hovered.0 = hit;
+    };
+
+    let fingers = touches.iter().count();
+    if gesture.finger.is_none() && fingers == 1 {
+        if let Some(touch) = touches.iter_just_pressed().next() {
+            // The UI has already seen the touch, so a node under it is no longer `None`.
+            let over_ui = ui_interactions
+                .iter()
+                .any(|interaction| *interaction != Interaction::None);
+            let start = Some(touch.position())
+                .filter(|_| !over_ui)
+                .and_then(&hit_at);
+            *gesture = TouchGesture {
+                finger: Some(touch.id()),
+                start,
+                started_at: time.elapsed_seconds(),
+                finished: start.is_none(),
+                ..default()
+            };
+            hover(start, &mut events);
+        }
+    }
+    let (Some(finger), Some(start)) = (gesture.finger, gesture.start) else {
+        gesture.finger = gesture
+            .finger
+            .filter(|finger| touches.get_pressed(*finger).is_some());
+        return;
+    };
+
+    if fingers > 1 && !gesture.finished {
+        if gesture.dragging {
+            events.send(TileEvent::Up(gesture.last.unwrap_or(start)));
+        }
+        gesture.finished = true;
+    }
+
+    if let Some(touch) = touches.get_pressed(finger) {
+        if gesture.finished {
+            return;
+        }
+        let hit = hit_at(touch.position());
+        if !gesture.dragging {
+            if hit.map(|hit| hit.hex) != Some(start.hex) {
+                events.send(TileEvent::Down(start));
+                gesture.dragging = true;
+            } else if time.elapsed_seconds() - gesture.started_at >= config.long_press_time {
+                events.send(TileEvent::LongPress(start));
+                gesture.finished = true;
+                return;
+            }
+        }
+        if hit.is_some() {
+            gesture.last = hit;
+            hover(hit, &mut events);
+        }
+        return;
+    }
+
+    if !gesture.finished {
+        if gesture.dragging {
+            events.send(TileEvent::Up(gesture.last.unwrap_or(start)));
+        } else if touches.just_released(finger) {
+            events.send(TileEvent::Down(start));
+            events.send(TileEvent::Up(start));
+        }
+    }
+    hover(None, &mut events);
+    *gesture = TouchGesture::default();
+}
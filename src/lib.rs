@@ -1,34 +1,109 @@
-#![allow(clippy::type_complexity)]
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
+use bevy::app::PluginGroupBuilder;
 use bevy::prelude::*;
 
-mod camera_plugin;
-mod config_plugin;
-mod grid_plugin;
-mod loading_plugin;
-mod ui_plugin;
+pub mod bindings_plugin;
+pub mod camera_plugin;
+pub mod config_plugin;
+pub mod grid_plugin;
+pub mod loading_plugin;
+pub mod map_plugin;
+pub mod palette_file;
+pub mod ui_plugin;
 
-use camera_plugin::CameraPlugin;
-use config_plugin::ConfigPlugin;
-use grid_plugin::GridPlugin;
-use loading_plugin::LoadingPlugin;
-use ui_plugin::UiPlugin;
+pub use bindings_plugin::{
+    Action, Binding, BindingsConfig, BindingsPlugin, InputBindings, InputButton, Modifier,
+    Rebinding, TextInputFocus,
+};
+pub use camera_plugin::{
+    CameraBookmark, CameraBookmarks, CameraConfig, CameraFocus, CameraPlugin, FrameCamera,
+};
+pub use config_plugin::{AppConfig, ConfigPlugin};
+pub use grid_plugin::{
+    EditorTool, EditorToolAppExt, GridConfig, GridPlugin, GridTracker, HexCoords, MapAccess,
+    MapLoaded, TilePlaced, TileRecolored, TileRemoved, TileVariant, Tool,
+};
+pub use loading_plugin::{LoadingPlugin, LoadingProgress};
+pub use map_plugin::{
+    ImportPalette, LoadMap, MapConfig, MapFile, MapLoadError, MapPlugin, NewMap, RecentMaps,
+    SaveMap,
+};
+pub use palette_file::{PaletteError, PaletteFormat};
+pub use ui_plugin::{UiConfig, UiPlugin};
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
-enum GameState {
+pub enum GameState {
     #[default]
     Loading,
+    /// Start a new map or open one. Headless apps skip it and go straight to editing.
+    MainMenu,
+    /// Editing the map; the grid and camera only run in this state.
     Playing,
+    /// Editing, on hold behind the pause menu.
+    Paused,
+    /// Interface options, opened from the main or pause menu.
+    Settings,
 }
 
-pub struct GamePlugin;
+/// Every plugin of the editor. Individual plugins can be configured with `set`, e.g.
+/// `GamePlugins.set(GridPlugin { config: GridConfig { radius: 8, ..default() } })`, or dropped
+/// with `disable` when the host app already provides them (`ConfigPlugin` adds `DefaultPlugins`).
+pub struct GamePlugins;
 
-impl Plugin for GamePlugin {
-    fn build(&self, app: &mut App) {
-        app.add_plugin(ConfigPlugin)
-            .add_plugin(LoadingPlugin)
-            .add_plugin(CameraPlugin)
-            .add_plugin(GridPlugin)
-            .add_plugin(UiPlugin);
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(ConfigPlugin::default())
+            .add(BindingsPlugin::default())
+            .add(LoadingPlugin)
+            .add(CameraPlugin::default())
+            .add(GridPlugin::default())
+            .add(MapPlugin::default())
+            .add(UiPlugin::default())
     }
 }
+
+/// The plugins that run without a window or GPU: map state, loading and events, but no camera or
+/// UI.
+pub struct HeadlessPlugins;
+
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(ConfigPlugin {
+                config: AppConfig {
+                    headless: true,
+                    ..default()
+                },
+            })
+            .add(BindingsPlugin {
+                config: BindingsConfig { path: None },
+            })
+            .add(LoadingPlugin)
+            .add(GridPlugin::default())
+            .add(MapPlugin {
+                config: MapConfig {
+                    recent_maps_path: None,
+                    ..default()
+                },
+            })
+    }
+}
+
+/// Builds an app from `plugins`, e.g. `HeadlessPlugins`, and updates it until the assets are
+/// loaded and the grid is set up, for tests, benchmarks and build machines. Panics if that takes
+/// more than 500 updates, e.g. because the assets folder is missing.
+pub fn ready_app(plugins: impl PluginGroup) -> App {
+    let mut app = App::new();
+    app.add_plugins(plugins);
+    for _ in 0..500 {
+        app.update();
+        if app.world.contains_resource::<GridTracker>() {
+            return app;
+        }
+        // Assets load on other threads; give them time instead of spinning.
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    panic!("grid was never set up");
+}
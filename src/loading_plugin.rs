@@ -1,25 +1,148 @@
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
+use crate::config_plugin::AppConfig;
 use crate::GameState;
 
+/// Dynamic asset collection listing the files behind every asset key.
+pub const MANIFEST: &str = "manifest.assets.ron";
+
 #[derive(AssetCollection, Resource)]
 pub struct LoadedAssets {
     #[asset(key = "fonts.hasklug")]
     pub font: Handle<Font>,
 }
 
+/// How far `GameState::Loading` has got, counted in files: the manifest, then every file it
+/// lists.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct LoadingProgress {
+    pub loaded: usize,
+    pub total: usize,
+    /// The first file still loading, if any.
+    pub current: Option<String>,
+    /// Files that could not be read or decoded. Loading never finishes once this is not empty.
+    pub failed: Vec<String>,
+}
+
+impl LoadingProgress {
+    /// Share of the files loaded, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.loaded as f32 / self.total as f32
+        }
+    }
+
+    /// What the loading screen says: the file being loaded, or the files that failed and how to
+    /// recover.
+    pub fn status(&self) -> String {
+        if !self.failed.is_empty() {
+            return format!(
+                "Could not load:\n{}\n\nCheck that the assets folder sits next to the game, then \
+                 restart it.",
+                self.failed.join("\n")
+            );
+        }
+        match &self.current {
+            Some(path) => format!("Loading {path} ({}/{})", self.loaded + 1, self.total),
+            None => "Starting".to_string(),
+        }
+    }
+}
+
+/// Keeps the manifest around while loading; `bevy_asset_loader` drops it once the keys are
+/// registered.
+#[derive(Resource)]
+struct LoadingManifest(Handle<StandardDynamicAssetCollection>);
+
 pub struct LoadingPlugin;
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
+        // Without a window there is no menu to pick a map from.
+        let headless = app
+            .world
+            .get_resource::<AppConfig>()
+            .is_some_and(|config| config.headless);
+        let next_state = if headless {
+            GameState::Playing
+        } else {
+            GameState::MainMenu
+        };
         app.add_state::<GameState>()
-            .add_loading_state(
-                LoadingState::new(GameState::Loading).continue_to_state(GameState::Playing),
-            )
+            .add_loading_state(LoadingState::new(GameState::Loading).continue_to_state(next_state))
             .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
                 GameState::Loading,
-                "manifest.assets.ron",
+                MANIFEST,
             )
-            .add_collection_to_loading_state::<_, LoadedAssets>(GameState::Loading);
+            .init_resource::<LoadingProgress>()
+            .add_system(load_manifest.in_schedule(OnEnter(GameState::Loading)))
+            .add_system(track_loading_progress.in_set(OnUpdate(GameState::Loading)))
+            // The last files can finish in the frame loading ends.
+            .add_systems(
+                (track_loading_progress, release_manifest)
+                    .chain()
+                    .in_schedule(OnExit(GameState::Loading)),
+            );
+    }
+}
+
+fn load_manifest(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LoadingManifest(asset_server.load(MANIFEST)));
+}
+
+fn release_manifest(mut commands: Commands) {
+    commands.remove_resource::<LoadingManifest>();
+}
+
+/// Counts the files of the manifest as the collections load them. Only files a collection asks
+/// for are loaded, so a headless app, which has no font or icons, never gets to the end.
+/// `bevy_asset_loader` can report progress itself with its `progress_tracking` feature, but that
+/// needs `iyes_progress`, which this project does not depend on.
+fn track_loading_progress(
+    asset_server: Res<AssetServer>,
+    manifest: Option<Res<LoadingManifest>>,
+    collections: Res<Assets<StandardDynamicAssetCollection>>,
+    mut progress: ResMut<LoadingProgress>,
+) {
+    let mut paths = vec![MANIFEST.to_string()];
+    let manifest = manifest.and_then(|manifest| collections.get(&manifest.0));
+    if let Some(manifest) = manifest {
+        let mut listed: Vec<&String> = manifest
+            .0
+            .values()
+            .flat_map(|asset| match asset {
+                StandardDynamicAsset::File { path } => vec![path],
+                StandardDynamicAsset::Files { paths } => paths.iter().collect(),
+                // Folders can't be listed without loading them.
+                _ => Vec::new(),
+            })
+            .collect();
+        listed.sort();
+        listed.dedup();
+        paths.extend(listed.into_iter().cloned());
+    }
+
+    let mut next = LoadingProgress {
+        total: paths.len(),
+        ..default()
+    };
+    for path in paths {
+        match asset_server.get_load_state(&path) {
+            LoadState::Loaded => next.loaded += 1,
+            LoadState::Failed => next.failed.push(path),
+            _ if next.current.is_none() => next.current = Some(path),
+            _ => {}
+        }
+    }
+    for path in next
+        .failed
+        .iter()
+        .filter(|path| !progress.failed.contains(path))
+    {
+        error!("could not load {path}, loading stopped");
     }
+    *progress = next;
 }
@@ -3,8 +3,14 @@
 
 use bevy::prelude::App;
 
-use bevy_app::GamePlugin;
+use bevy_app::{GamePlugins, HeadlessPlugins};
 
 fn main() {
-    App::new().add_plugin(GamePlugin).run();
+    let mut app = App::new();
+    if std::env::args().any(|arg| arg == "--headless") {
+        app.add_plugins(HeadlessPlugins);
+    } else {
+        app.add_plugins(GamePlugins);
+    }
+    app.run();
 }
@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy::window::FileDragAndDrop;
+use hexx::{shapes, Hex};
+use serde::{Deserialize, Serialize};
+
+use crate::bindings_plugin::Action;
+use crate::camera_plugin::{CameraBookmark, CameraBookmarks};
+use crate::grid_plugin::{
+    GridConfig, GridTracker, HexCoords, MapLoaded, Palette, Tile, TileVariant,
+};
+use crate::palette_file::{read_palette, PaletteFormat};
+use crate::GameState;
+
+#[derive(Resource, Clone, Debug)]
+pub struct MapConfig {
+    /// File written by the `SaveMap` action and read by the `LoadMap` action.
+    pub path: PathBuf,
+    /// RON file listing the maps saved and loaded last, kept across runs. `None` keeps the list
+    /// for this run only.
+    pub recent_maps_path: Option<PathBuf>,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("map.ron"),
+            recent_maps_path: Some(PathBuf::from("recent_maps.ron")),
+        }
+    }
+}
+
+/// Maps saved or loaded lately, most recent first.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct RecentMaps(pub Vec<PathBuf>);
+
+impl RecentMaps {
+    pub const LIMIT: usize = 8;
+
+    /// Moves `path` to the front, dropping the oldest entry once there are more than `LIMIT`.
+    pub fn push(&mut self, path: PathBuf) {
+        self.0.retain(|recent| *recent != path);
+        self.0.insert(0, path);
+        self.0.truncate(Self::LIMIT);
+    }
+}
+
+/// Replaces the current map with an empty board of `GridConfig::radius` rings and the default
+/// palette.
+#[derive(Clone, Debug)]
+pub struct NewMap;
+
+/// Writes the current map to `path`.
+#[derive(Clone, Debug)]
+pub struct SaveMap {
+    pub path: PathBuf,
+}
+
+/// Replaces the current map with the one stored at `path`.
+#[derive(Clone, Debug)]
+pub struct LoadMap {
+    pub path: PathBuf,
+}
+
+/// Why the last `LoadMap` failed, cleared once a map loads. The menus stay open and show it.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct MapLoadError(pub Option<String>);
+
+/// Replaces the palette with the colors of the palette file at `path`. Palette files dropped on
+/// the window are imported too.
+#[derive(Clone, Debug)]
+pub struct ImportPalette {
+    pub path: PathBuf,
+}
+
+/// One hex of the board and the tiles stacked on it, bottom first.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ColumnData {
+    pub hex: (i32, i32),
+    pub tiles: Vec<TileVariant>,
+}
+
+/// Everything saved with a map, stored as RON.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MapFile {
+    pub columns: Vec<ColumnData>,
+    #[serde(default)]
+    pub camera_bookmarks: BTreeMap<u8, CameraBookmark>,
+    /// The palette, including colors made at runtime. Maps without one get the default palette.
+    #[serde(default)]
+    pub palette: Vec<TileVariant>,
+}
+
+impl MapFile {
+    /// A board of `radius` rings around the center without any tiles.
+    pub fn empty(radius: u32) -> Self {
+        let mut columns: Vec<ColumnData> = shapes::hexagon(Hex::ZERO, radius)
+            .map(|hex| ColumnData {
+                hex: (hex.x, hex.y),
+                tiles: Vec::new(),
+            })
+            .collect();
+        columns.sort_by_key(|column| column.hex);
+        Self {
+            columns,
+            ..default()
+        }
+    }
+
+    pub fn from_tracker(tracker: &GridTracker) -> Self {
+        let mut columns: Vec<ColumnData> = tracker
+            .columns
+            .iter()
+            .map(|(hex, column)| ColumnData {
+                hex: (hex.x, hex.y),
+                tiles: column.iter().map(|tile| tile.variant.clone()).collect(),
+            })
+            .collect();
+        columns.sort_by_key(|column| column.hex);
+        Self {
+            columns,
+            camera_bookmarks: BTreeMap::new(),
+            palette: Vec::new(),
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    /// The first hex listed by more than one column. Such files are refused, as neither column
+    /// can be preferred.
+    pub fn duplicate_column(&self) -> Option<(i32, i32)> {
+        let mut seen = HashSet::new();
+        self.columns
+            .iter()
+            .map(|column| column.hex)
+            .find(|hex| !seen.insert(*hex))
+    }
+}
+
+#[derive(Default)]
+pub struct MapPlugin {
+    pub config: MapConfig,
+}
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        let recent_maps = self
+            .config
+            .recent_maps_path
+            .as_ref()
+            .filter(|path| path.exists())
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|source| ron::from_str(&source).map_err(|error| error.to_string()))
+                    .unwrap_or_else(|error| {
+                        error!(
+                            "could not read recent maps from {}: {error}",
+                            path.display()
+                        );
+                        Vec::new()
+                    })
+            })
+            .unwrap_or_default();
+
+        app.insert_resource(self.config.clone())
+            .insert_resource(RecentMaps(recent_maps))
+            .init_resource::<MapLoadError>()
+            .add_event::<SaveMap>()
+            .add_event::<LoadMap>()
+            .add_event::<NewMap>()
+            .add_event::<ImportPalette>()
+            .add_event::<FileDragAndDrop>()
+            .add_system(map_shortcuts.in_set(OnUpdate(GameState::Playing)))
+            // Menus open maps outside of editing, but there is no map before loading is done.
+            .add_systems(
+                (import_dropped_palettes, save_map, load_map, import_palette)
+                    .chain()
+                    .after(map_shortcuts)
+                    .distributive_run_if(resource_exists::<GridTracker>()),
+            );
+    }
+}
+
+fn map_shortcuts(
+    actions: Res<Input<Action>>,
+    config: Res<MapConfig>,
+    mut save: EventWriter<SaveMap>,
+    mut load: EventWriter<LoadMap>,
+) {
+    if actions.just_pressed(Action::SaveMap) {
+        save.send(SaveMap {
+            path: config.path.clone(),
+        });
+    }
+    if actions.just_pressed(Action::LoadMap) {
+        load.send(LoadMap {
+            path: config.path.clone(),
+        });
+    }
+}
+
+fn save_map(
+    mut events: EventReader<SaveMap>,
+    config: Res<MapConfig>,
+    mut recent_maps: ResMut<RecentMaps>,
+    tracker: Res<GridTracker>,
+    palette: Res<Palette>,
+    bookmarks: Option<Res<CameraBookmarks>>,
+) {
+    for event in events.iter() {
+        let mut map = MapFile::from_tracker(&tracker);
+        map.palette = palette.0.clone();
+        if let Some(bookmarks) = &bookmarks {
+            map.camera_bookmarks = bookmarks.0.clone();
+        }
+        let result = map
+            .to_ron()
+            .map_err(|error| error.to_string())
+            .and_then(|source| fs::write(&event.path, source).map_err(|error| error.to_string()));
+        match result {
+            Ok(()) => {
+                info!("saved map to {}", event.path.display());
+                remember(&mut recent_maps, &config, &event.path);
+            }
+            Err(error) => error!("could not save map to {}: {error}", event.path.display()),
+        }
+    }
+}
+
+fn load_map(
+    mut commands: Commands,
+    mut events: EventReader<LoadMap>,
+    mut new_maps: EventReader<NewMap>,
+    config: Res<MapConfig>,
+    grid_config: Res<GridConfig>,
+    mut recent_maps: ResMut<RecentMaps>,
+    mut tracker: ResMut<GridTracker>,
+    mut palette: ResMut<Palette>,
+    bookmarks: Option<ResMut<CameraBookmarks>>,
+    mut map_loaded: EventWriter<MapLoaded>,
+    mut load_error: ResMut<MapLoadError>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let new_map = new_maps.iter().count() > 0;
+    let map = match events.iter().last() {
+        Some(event) => {
+            let map = fs::read_to_string(&event.path)
+                .map_err(|error| error.to_string())
+                .and_then(|source| MapFile::from_ron(&source).map_err(|error| error.to_string()))
+                .and_then(|map| match map.duplicate_column() {
+                    Some((x, y)) => Err(format!("column ({x}, {y}) is listed more than once")),
+                    None => Ok(map),
+                });
+            match map {
+                Ok(map) => {
+                    remember(&mut recent_maps, &config, &event.path);
+                    map
+                }
+                Err(error) => {
+                    error!("could not load map from {}: {error}", event.path.display());
+                    load_error.0 =
+                        Some(format!("Could not load {}: {error}", event.path.display()));
+                    return;
+                }
+            }
+        }
+        None if new_map => MapFile::empty(grid_config.radius),
+        None => return,
+    };
+
+    for entity in tracker.tiles.keys() {
+        commands.entity(*entity).despawn();
+    }
+    tracker.tiles.clear();
+    tracker.columns.clear();
+    // Hand-edited files can list a color twice or more than the palette row holds.
+    let mut listed = Palette(Vec::new());
+    for variant in map.palette {
+        if !listed.add(variant) {
+            warn!("the palette of the map is full, later colors are left out");
+            break;
+        }
+    }
+    *palette = if listed.0.is_empty() {
+        Palette::default()
+    } else {
+        listed
+    };
+    for column in map.columns {
+        let hex = Hex::new(column.hex.0, column.hex.1);
+        let tiles: Vec<Tile> = column
+            .tiles
+            .into_iter()
+            .enumerate()
+            .map(|(layer, variant)| {
+                // Colors used on the map stay at hand, as long as the palette has room.
+                palette.add(variant.clone());
+                let entity = commands.spawn(variant.clone()).id();
+                tracker
+                    .tiles
+                    .insert(entity, HexCoords::new(hex, layer as u32));
+                Tile { entity, variant }
+            })
+            .collect();
+        tracker.columns.insert(hex, tiles);
+    }
+    tracker.drag_layer = None;
+    tracker.last_hex = Hex::ZERO;
+    if !palette.0.contains(&tracker.current_tile_variant) {
+        tracker.current_tile_variant = palette.0[0].clone();
+    }
+
+    if let Some(mut bookmarks) = bookmarks {
+        bookmarks.0 = map.camera_bookmarks;
+    }
+    map_loaded.send(MapLoaded {
+        tile_count: tracker.tiles.len(),
+    });
+    load_error.0 = None;
+    // Maps opened from the main menu are edited once they are in place.
+    if state.0 != GameState::Playing {
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// Puts `path` at the top of the recent maps and writes the list out.
+fn remember(recent_maps: &mut RecentMaps, config: &MapConfig, path: &Path) {
+    recent_maps.push(path.to_path_buf());
+    let Some(recent_maps_path) = &config.recent_maps_path else {
+        return;
+    };
+    let result = ron::ser::to_string_pretty(&recent_maps.0, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|source| fs::write(recent_maps_path, source).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        error!(
+            "could not write recent maps to {}: {error}",
+            recent_maps_path.display()
+        );
+    }
+}
+
+fn import_dropped_palettes(
+    mut drops: EventReader<FileDragAndDrop>,
+    mut imports: EventWriter<ImportPalette>,
+) {
+    for drop in drops.iter() {
+        if let FileDragAndDrop::DroppedFile { path_buf, .. } = drop {
+            if PaletteFormat::from_path(path_buf).is_some() {
+                imports.send(ImportPalette {
+                    path: path_buf.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn import_palette(
+    mut events: EventReader<ImportPalette>,
+    mut palette: ResMut<Palette>,
+    mut tracker: ResMut<GridTracker>,
+) {
+    let Some(event) = events.iter().last() else {
+        return;
+    };
+    let imported = match read_palette(&event.path) {
+        Ok(imported) => imported,
+        Err(error) => {
+            error!(
+                "could not import palette from {}: {error}",
+                event.path.display()
+            );
+            return;
+        }
+    };
+    if !imported.0.contains(&tracker.current_tile_variant) {
+        tracker.current_tile_variant = imported.0[0].clone();
+    }
+    info!(
+        "imported {} colors from {}",
+        imported.0.len(),
+        event.path.display()
+    );
+    *palette = imported;
+}
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::grid_plugin::{Palette, TileVariant};
+
+/// The palette files colors can be imported from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// GIMP `.gpl`: a `GIMP Palette` header, then one `red green blue [name]` line per color.
+    Gpl,
+    /// Paint.NET `.txt`: one `AARRGGBB` line per color, `;` starting comments.
+    PaintNet,
+    /// `.hex`: one `RRGGBB` line per color.
+    Hex,
+    /// Adobe swatch exchange `.ase`, with RGB, CMYK or gray colors.
+    Ase,
+}
+
+impl PaletteFormat {
+    /// The format files with `path`'s extension are read as.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gpl" => Some(PaletteFormat::Gpl),
+            "txt" => Some(PaletteFormat::PaintNet),
+            "hex" => Some(PaletteFormat::Hex),
+            "ase" => Some(PaletteFormat::Ase),
+            _ => None,
+        }
+    }
+}
+
+/// Why a palette file could not be imported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaletteError {
+    UnknownFormat,
+    /// The extension names a format the content does not look like.
+    UnrecognizedFormat,
+    Read(String),
+    NotText,
+    /// A line of a text format, counted from 1.
+    Line {
+        line: usize,
+        message: String,
+    },
+    /// The binary layout of an `.ase` file.
+    Ase(String),
+    Empty,
+    TooManyColors {
+        count: usize,
+    },
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::UnknownFormat => {
+                write!(f, "not a palette file, expected .gpl, .txt, .hex or .ase")
+            }
+            PaletteError::UnrecognizedFormat => write!(f, "unrecognized palette format"),
+            PaletteError::Read(error) => write!(f, "{error}"),
+            PaletteError::NotText => write!(f, "file is not UTF-8 text"),
+            PaletteError::Line { line, message } => write!(f, "line {line}: {message}"),
+            PaletteError::Ase(message) => write!(f, "invalid swatch file: {message}"),
+            PaletteError::Empty => write!(f, "file has no colors"),
+            PaletteError::TooManyColors { count } => write!(
+                f,
+                "file has {count} colors, the palette holds at most {}",
+                Palette::CAPACITY
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+/// Reads the palette stored at `path`, in the format its extension names.
+pub fn read_palette(path: &Path) -> Result<Palette, PaletteError> {
+    let format = PaletteFormat::from_path(path).ok_or(PaletteError::UnknownFormat)?;
+    let bytes = fs::read(path).map_err(|error| PaletteError::Read(error.to_string()))?;
+    parse_palette(format, &bytes)
+}
+
+/// One tile variant per distinct color of the file, in file order.
+pub fn parse_palette(format: PaletteFormat, bytes: &[u8]) -> Result<Palette, PaletteError> {
+    let colors = match format {
+        PaletteFormat::Ase => parse_ase(bytes)?,
+        format => {
+            let text = std::str::from_utf8(bytes).map_err(|_| PaletteError::NotText)?;
+            match format {
+                PaletteFormat::Gpl => parse_gpl(text)?,
+                PaletteFormat::PaintNet => parse_paint_net(text)?,
+                _ => parse_hex(text)?,
+            }
+        }
+    };
+    let mut variants: Vec<TileVariant> = Vec::new();
+    for variant in colors.into_iter().map(TileVariant::custom) {
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+    match variants.len() {
+        0 => Err(PaletteError::Empty),
+        count if count > Palette::CAPACITY => Err(PaletteError::TooManyColors { count }),
+        _ => Ok(Palette(variants)),
+    }
+}
+
+/// The lines of `text` that are not blank, numbered from 1 and trimmed.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn line_error(line: usize, message: &str) -> PaletteError {
+    PaletteError::Line {
+        line,
+        message: message.to_string(),
+    }
+}
+
+fn parse_gpl(text: &str) -> Result<Vec<Color>, PaletteError> {
+    let mut lines = lines(text);
+    match lines.next() {
+        Some((_, "GIMP Palette")) => {}
+        Some((line, _)) => return Err(line_error(line, "expected a `GIMP Palette` header")),
+        None => return Err(PaletteError::Empty),
+    }
+    lines
+        .filter(|(_, line)| {
+            !line.starts_with('#') && !line.starts_with("Name:") && !line.starts_with("Columns:")
+        })
+        .map(|(line, content)| {
+            let channels: Option<Vec<u8>> = content
+                .split_whitespace()
+                .take(3)
+                .map(|channel| channel.parse().ok())
+                .collect();
+            match channels.as_deref() {
+                Some(&[r, g, b]) => Ok(Color::rgb_u8(r, g, b)),
+                _ => Err(line_error(
+                    line,
+                    "expected red, green and blue from 0 to 255",
+                )),
+            }
+        })
+        .collect()
+}
+
+/// `.txt` is used by far more than Paint.NET, whose files start with comments or opaque colors.
+fn looks_like_paint_net(text: &str) -> bool {
+    match lines(text).next() {
+        Some((_, first)) => {
+            first.starts_with(';')
+                || (first.len() == 8
+                    && first
+                        .get(..2)
+                        .is_some_and(|alpha| alpha.eq_ignore_ascii_case("ff")))
+        }
+        // Left to report the file as empty.
+        None => true,
+    }
+}
+
+fn parse_paint_net(text: &str) -> Result<Vec<Color>, PaletteError> {
+    if !looks_like_paint_net(text) {
+        return Err(PaletteError::UnrecognizedFormat);
+    }
+    lines(text)
+        .filter(|(_, line)| !line.starts_with(';'))
+        .map(|(line, content)| {
+            // The alpha byte comes first and is dropped, tiles are opaque.
+            let rgb = content.get(2..).filter(|_| content.len() == 8);
+            rgb.and_then(hex_color)
+                .ok_or_else(|| line_error(line, "expected eight hex digits, AARRGGBB"))
+        })
+        .collect()
+}
+
+fn parse_hex(text: &str) -> Result<Vec<Color>, PaletteError> {
+    lines(text)
+        .map(|(line, content)| {
+            hex_color(content.trim_start_matches('#'))
+                .ok_or_else(|| line_error(line, "expected six hex digits, RRGGBB"))
+        })
+        .collect()
+}
+
+/// Parses exactly six hex digits.
+fn hex_color(digits: &str) -> Option<Color> {
+    if digits.len() != 6 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).ok();
+    Some(Color::rgb_u8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Big-endian reads from an `.ase` file, failing at its end.
+struct AseReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> AseReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], PaletteError> {
+        if self.bytes.len() < count {
+            return Err(PaletteError::Ase("file ends early".to_string()));
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> Result<u16, PaletteError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, PaletteError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, PaletteError> {
+        Ok(f32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+const ASE_COLOR_ENTRY: u16 = 0x0001;
+
+fn parse_ase(bytes: &[u8]) -> Result<Vec<Color>, PaletteError> {
+    let mut reader = AseReader { bytes };
+    if reader.take(4).ok() != Some(b"ASEF".as_slice()) {
+        return Err(PaletteError::Ase("missing `ASEF` signature".to_string()));
+    }
+    reader.take(4)?; // version
+    let block_count = reader.u32()?;
+    let mut colors = Vec::new();
+    for _ in 0..block_count {
+        let block_type = reader.u16()?;
+        let length = reader.u32()? as usize;
+        let mut block = AseReader {
+            bytes: reader.take(length)?,
+        };
+        // Group starts and ends only matter for how swatches are shown in other programs.
+        if block_type != ASE_COLOR_ENTRY {
+            continue;
+        }
+        let name_length = block.u16()? as usize;
+        block.take(name_length * 2)?;
+        let model = block.take(4)?;
+        let color = match model {
+            b"RGB " => Color::rgb(block.f32()?, block.f32()?, block.f32()?),
+            b"CMYK" => {
+                let [cyan, magenta, yellow, key] =
+                    [block.f32()?, block.f32()?, block.f32()?, block.f32()?];
+                Color::rgb(
+                    (1.0 - cyan) * (1.0 - key),
+                    (1.0 - magenta) * (1.0 - key),
+                    (1.0 - yellow) * (1.0 - key),
+                )
+            }
+            b"Gray" => {
+                let gray = block.f32()?;
+                Color::rgb(gray, gray, gray)
+            }
+            model => {
+                return Err(PaletteError::Ase(format!(
+                    "unsupported color model `{}`",
+                    String::from_utf8_lossy(model).trim()
+                )))
+            }
+        };
+        colors.push(color);
+    }
+    Ok(colors)
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+
+use crate::bindings_plugin::{Action, InputBindings, Rebinding};
+use crate::grid_plugin::EditorTools;
+use crate::loading_plugin::LoadedAssets;
+
+const FONT_SIZE: f32 = 14.;
+const TEXT_COLOR: Color = Color::PURPLE;
+const WAITING_COLOR: Color = Color::ORANGE_RED;
+
+/// Root of the panel listing every action and its bindings, hidden until `ShowBindings`.
+#[derive(Component)]
+pub struct BindingsMenu;
+
+/// A row of the panel; clicking it waits for a new binding of the action.
+#[derive(Component)]
+pub struct BindingEntry(pub Action);
+
+/// Line at the top of the panel telling what a click does, or which actions clash.
+#[derive(Component)]
+pub struct BindingsStatus;
+
+pub fn spawn_bindings_menu(
+    mut commands: Commands,
+    assets: Res<LoadedAssets>,
+    tools: Res<EditorTools>,
+) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: FONT_SIZE,
+        color: TEXT_COLOR,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    position: UiRect::all(Val::Percent(5.0)),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    gap: Size::all(Val::Px(6.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(1.0, 1.0, 1.0, 0.9).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            // Keeps clicks between the entries from reaching the board.
+            Interaction::default(),
+            BindingsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                BindingsStatus,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        flex_wrap: FlexWrap::Wrap,
+                        align_content: AlignContent::FlexStart,
+                        flex_grow: 1.0,
+                        gap: Size::new(Val::Px(16.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for action in Action::with_tools(tools.iter().map(|(tool, _)| tool)) {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                BindingEntry(action),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section("", text_style.clone()));
+                            });
+                    }
+                });
+        });
+}
+
+pub fn toggle_bindings_menu(
+    actions: Res<Input<Action>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut menus: Query<&mut Style, With<BindingsMenu>>,
+) {
+    if !actions.just_pressed(Action::ShowBindings) {
+        return;
+    }
+    for mut style in &mut menus {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            _ => {
+                rebinding.action = None;
+                Display::None
+            }
+        };
+    }
+}
+
+/// Closes the panel and drops any pending rebind, when leaving the screen it was opened from.
+pub fn hide_bindings_menu(
+    mut rebinding: ResMut<Rebinding>,
+    mut menus: Query<&mut Style, With<BindingsMenu>>,
+) {
+    rebinding.action = None;
+    for mut style in &mut menus {
+        style.display = Display::None;
+    }
+}
+
+pub fn bindings_menu_system(
+    bindings: Res<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    clicked: Query<(&Interaction, &BindingEntry), Changed<Interaction>>,
+    entries: Query<(&BindingEntry, &Children)>,
+    mut status: Query<&mut Text, With<BindingsStatus>>,
+    mut texts: Query<&mut Text, Without<BindingsStatus>>,
+) {
+    for (interaction, entry) in &clicked {
+        if *interaction == Interaction::Clicked {
+            rebinding.action = Some(entry.0);
+            rebinding.conflicts.clear();
+        }
+    }
+    if !bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    for mut text in &mut status {
+        text.sections[0].value = match (rebinding.action, rebinding.conflicts.as_slice()) {
+            (Some(action), _) => format!("Press a key or button for {action}, Escape to cancel"),
+            (None, []) => "Click an action to rebind it".to_string(),
+            (None, conflicts) => format!(
+                "Also bound to: {}",
+                conflicts
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+    }
+    for (entry, children) in &entries {
+        let Some(mut text) = children
+            .first()
+            .and_then(|child| texts.get_mut(*child).ok())
+        else {
+            continue;
+        };
+        let waiting = rebinding.action == Some(entry.0);
+        let listed = bindings
+            .get(entry.0)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        text.sections[0].value = if waiting {
+            format!("{}: ...", entry.0)
+        } else {
+            format!("{}: {listed}", entry.0)
+        };
+        text.sections[0].style.color = if waiting || rebinding.conflicts.contains(&entry.0) {
+            WAITING_COLOR
+        } else {
+            TEXT_COLOR
+        };
+    }
+}
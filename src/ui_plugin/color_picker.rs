@@ -0,0 +1,455 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::ReceivedCharacter;
+
+use crate::bindings_plugin::TextInputFocus;
+use crate::grid_plugin::{GridTracker, Palette, TileVariant};
+use crate::loading_plugin::LoadedAssets;
+
+use super::hud::HudPart;
+
+const FONT_SIZE: f32 = 14.;
+const TEXT_COLOR: Color = Color::PURPLE;
+const FIELD_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.1);
+const FOCUSED_FIELD_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.25);
+/// Number of blocks each slider's gradient is drawn with.
+const SEGMENTS: usize = 24;
+
+/// The part of the color a slider sets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HslChannel {
+    Hue,
+    Saturation,
+    Lightness,
+}
+
+impl HslChannel {
+    const ALL: [HslChannel; 3] = [
+        HslChannel::Hue,
+        HslChannel::Saturation,
+        HslChannel::Lightness,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            HslChannel::Hue => "H",
+            HslChannel::Saturation => "S",
+            HslChannel::Lightness => "L",
+        }
+    }
+}
+
+/// The color being made in the picker panel.
+#[derive(Resource, Clone, Debug)]
+pub struct ColorPicker {
+    /// In degrees.
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+    /// Digits typed into the hex field while it has focus.
+    pub hex_input: Option<String>,
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        Self {
+            hue: 200.0,
+            saturation: 0.6,
+            lightness: 0.5,
+            hex_input: None,
+        }
+    }
+}
+
+impl ColorPicker {
+    pub fn color(&self) -> Color {
+        Color::hsl(self.hue, self.saturation, self.lightness)
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        let [hue, saturation, lightness, _] = color.as_hsla_f32();
+        self.hue = hue;
+        self.saturation = saturation;
+        self.lightness = lightness;
+    }
+
+    /// Position of `channel`'s slider, from 0 to 1.
+    fn get(&self, channel: HslChannel) -> f32 {
+        match channel {
+            HslChannel::Hue => self.hue / 360.0,
+            HslChannel::Saturation => self.saturation,
+            HslChannel::Lightness => self.lightness,
+        }
+    }
+
+    fn set(&mut self, channel: HslChannel, position: f32) {
+        let position = position.clamp(0.0, 1.0);
+        match channel {
+            HslChannel::Hue => self.hue = position * 360.0,
+            HslChannel::Saturation => self.saturation = position,
+            HslChannel::Lightness => self.lightness = position,
+        }
+    }
+
+    /// The color with `channel` moved to `position` and the others kept.
+    fn color_at(&self, channel: HslChannel, position: f32) -> Color {
+        let mut picker = self.clone();
+        picker.set(channel, position);
+        picker.color()
+    }
+}
+
+/// The last palette button, opening and closing the picker.
+#[derive(Component)]
+pub struct AddColorButton;
+
+/// Root of the picker panel, hidden until `AddColorButton` is clicked.
+#[derive(Component)]
+pub struct ColorPickerPanel;
+
+/// Clicking or dragging along it sets the channel.
+#[derive(Component)]
+pub struct SliderTrack(pub HslChannel);
+
+/// One block of a slider's gradient.
+#[derive(Component)]
+pub struct SliderSegment {
+    pub channel: HslChannel,
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct SliderThumb(pub HslChannel);
+
+/// Takes typed hex digits once clicked; Enter or a click elsewhere applies them, Escape drops
+/// them.
+#[derive(Component)]
+pub struct HexField;
+
+#[derive(Component)]
+pub struct HexFieldText;
+
+#[derive(Component)]
+pub struct ColorPreview;
+
+/// Adds the picked color to the palette.
+#[derive(Component)]
+pub struct AddVariantButton;
+
+#[derive(Component)]
+pub struct ColorPickerStatus;
+
+pub fn spawn_color_picker(mut commands: Commands, assets: Res<LoadedAssets>) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: FONT_SIZE,
+        color: TEXT_COLOR,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        right: Val::Percent(2.0),
+                        bottom: Val::Percent(14.0),
+                        ..default()
+                    },
+                    size: Size::width(Val::Px(260.0)),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    gap: Size::all(Val::Px(6.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(1.0, 1.0, 1.0, 0.9).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            // Keeps clicks on the panel from reaching the board.
+            Interaction::default(),
+            ColorPickerPanel,
+            HudPart::Always,
+        ))
+        .with_children(|parent| {
+            for channel in HslChannel::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            gap: Size::all(Val::Px(6.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            channel.label(),
+                            text_style.clone(),
+                        ));
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        size: Size::new(Val::Percent(100.0), Val::Px(16.0)),
+                                        flex_grow: 1.0,
+                                        ..default()
+                                    },
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                RelativeCursorPosition::default(),
+                                SliderTrack(channel),
+                            ))
+                            .with_children(|parent| {
+                                for index in 0..SEGMENTS {
+                                    parent.spawn((
+                                        NodeBundle {
+                                            style: Style {
+                                                size: Size::new(
+                                                    Val::Percent(100.0 / SEGMENTS as f32),
+                                                    Val::Percent(100.0),
+                                                ),
+                                                ..default()
+                                            },
+                                            ..default()
+                                        },
+                                        SliderSegment { channel, index },
+                                    ));
+                                }
+                                parent.spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            position_type: PositionType::Absolute,
+                                            size: Size::new(Val::Px(3.0), Val::Percent(100.0)),
+                                            ..default()
+                                        },
+                                        background_color: Color::WHITE.into(),
+                                        ..default()
+                                    },
+                                    SliderThumb(channel),
+                                ));
+                            });
+                    });
+            }
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        gap: Size::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(32.0), Val::Px(24.0)),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        ColorPreview,
+                    ));
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    size: Size::height(Val::Px(24.0)),
+                                    flex_grow: 1.0,
+                                    align_items: AlignItems::Center,
+                                    padding: UiRect::horizontal(Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: FIELD_COLOR.into(),
+                                ..default()
+                            },
+                            HexField,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section("", text_style.clone()),
+                                HexFieldText,
+                            ));
+                        });
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    size: Size::height(Val::Px(24.0)),
+                                    align_items: AlignItems::Center,
+                                    padding: UiRect::horizontal(Val::Px(8.0)),
+                                    ..default()
+                                },
+                                background_color: FIELD_COLOR.into(),
+                                ..default()
+                            },
+                            AddVariantButton,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section("Add", text_style.clone()));
+                        });
+                });
+            parent.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                ColorPickerStatus,
+            ));
+        });
+}
+
+pub fn toggle_color_picker(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<AddColorButton>)>,
+    mut picker: ResMut<ColorPicker>,
+    mut panels: Query<&mut Style, With<ColorPickerPanel>>,
+) {
+    if !buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked)
+    {
+        return;
+    }
+    for mut style in &mut panels {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            _ => {
+                picker.hex_input = None;
+                Display::None
+            }
+        };
+    }
+}
+
+pub fn color_slider_system(
+    tracks: Query<(&Interaction, &RelativeCursorPosition, &SliderTrack)>,
+    mut picker: ResMut<ColorPicker>,
+) {
+    for (interaction, cursor, track) in &tracks {
+        let Some(position) = cursor
+            .normalized
+            .filter(|_| *interaction == Interaction::Clicked)
+        else {
+            continue;
+        };
+        if (picker.get(track.0) - position.x.clamp(0.0, 1.0)).abs() > f32::EPSILON {
+            picker.set(track.0, position.x);
+        }
+    }
+}
+
+pub fn hex_field_system(
+    fields: Query<&Interaction, With<HexField>>,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut picker: ResMut<ColorPicker>,
+    mut focus: ResMut<TextInputFocus>,
+    mut backgrounds: Query<&mut BackgroundColor, With<HexField>>,
+) {
+    let field_clicked = fields
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if let Some(input) = picker.hex_input.clone() {
+        if keys.just_pressed(KeyCode::Escape) {
+            picker.hex_input = None;
+        } else if keys.just_pressed(KeyCode::Return)
+            || (mouse.just_pressed(MouseButton::Left) && !field_clicked)
+        {
+            // Anything that is not a color leaves the picked one as it was.
+            if let Ok(color) = Color::hex(&input) {
+                picker.set_color(color);
+            }
+            picker.hex_input = None;
+        } else {
+            let mut edited = input.clone();
+            if keys.just_pressed(KeyCode::Back) {
+                edited.pop();
+            }
+            for event in characters.iter() {
+                if event.char.is_ascii_hexdigit() && edited.len() < 6 {
+                    edited.push(event.char.to_ascii_lowercase());
+                }
+            }
+            if edited != input {
+                picker.hex_input = Some(edited);
+            }
+        }
+    } else if field_clicked && mouse.just_pressed(MouseButton::Left) {
+        let hex = TileVariant::custom(picker.color()).to_string();
+        picker.hex_input = Some(hex.trim_start_matches('#').to_string());
+    }
+    characters.clear();
+
+    let editing = picker.hex_input.is_some();
+    if focus.0 != editing {
+        focus.0 = editing;
+    }
+    let color = if editing {
+        FOCUSED_FIELD_COLOR
+    } else {
+        FIELD_COLOR
+    };
+    for mut background_color in &mut backgrounds {
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+}
+
+/// Redraws the gradients, thumbs, preview and hex field after the picked color changes.
+pub fn update_color_picker(
+    picker: Res<ColorPicker>,
+    mut segments: Query<(&SliderSegment, &mut BackgroundColor), Without<ColorPreview>>,
+    mut thumbs: Query<(&SliderThumb, &mut Style)>,
+    mut previews: Query<&mut BackgroundColor, With<ColorPreview>>,
+    mut texts: Query<&mut Text, With<HexFieldText>>,
+) {
+    if !picker.is_changed() {
+        return;
+    }
+    for (segment, mut background_color) in &mut segments {
+        let position = (segment.index as f32 + 0.5) / SEGMENTS as f32;
+        background_color.0 = picker.color_at(segment.channel, position);
+    }
+    for (thumb, mut style) in &mut thumbs {
+        style.position.left = Val::Percent(picker.get(thumb.0) * 100.0);
+    }
+    for mut background_color in &mut previews {
+        background_color.0 = picker.color();
+    }
+    let hex = match &picker.hex_input {
+        Some(input) => format!("#{input}_"),
+        None => TileVariant::custom(picker.color()).to_string(),
+    };
+    for mut text in &mut texts {
+        text.sections[0].value = hex.clone();
+    }
+}
+
+/// Appends the picked color to the palette as a new variant and selects it.
+pub fn add_color_variant(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<AddVariantButton>)>,
+    picker: Res<ColorPicker>,
+    mut palette: ResMut<Palette>,
+    mut tracker: ResMut<GridTracker>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut status: Query<&mut Text, With<ColorPickerStatus>>,
+) {
+    if !buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked)
+    {
+        return;
+    }
+    let variant = TileVariant::custom(picker.color());
+    let message = if palette.add(variant.clone()) {
+        tracker.tile_material(&variant, &mut materials);
+        tracker.current_tile_variant = variant;
+        String::new()
+    } else {
+        format!("The palette holds at most {} colors", Palette::CAPACITY)
+    };
+    for mut text in &mut status {
+        text.sections[0].value = message.clone();
+    }
+}
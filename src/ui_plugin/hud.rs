@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+
+use super::UiConfig;
+
+/// A root of the editor's on-screen interface, shown while editing or paused, unless its
+/// `UiConfig` setting turns it off.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudPart {
+    Always,
+    Instructions,
+    StatusBar,
+    Minimap,
+}
+
+impl HudPart {
+    fn enabled(self, config: &UiConfig) -> bool {
+        match self {
+            HudPart::Always => true,
+            HudPart::Instructions => config.show_instructions,
+            HudPart::StatusBar => config.show_status_bar,
+            HudPart::Minimap => config.show_minimap,
+        }
+    }
+}
+
+pub fn update_hud_visibility(
+    state: Res<State<GameState>>,
+    config: Res<UiConfig>,
+    mut parts: Query<(&HudPart, &mut Visibility)>,
+) {
+    if !state.is_changed() && !config.is_changed() {
+        return;
+    }
+    let editing = matches!(state.0, GameState::Playing | GameState::Paused);
+    for (part, mut visibility) in &mut parts {
+        let shown = if editing && part.enabled(&config) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+}
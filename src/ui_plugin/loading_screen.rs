@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy_asset_loader::prelude::*;
+
+use crate::loading_plugin::LoadingProgress;
+
+/// Key of `LoadedAssets::font`. The screen appears before the manifest is read, so until the key
+/// is known and its file loaded only the bar is drawn.
+const FONT_KEY: &str = "fonts.hasklug";
+const BAR_WIDTH: f32 = 300.0;
+const BAR_HEIGHT: f32 = 12.0;
+const TRACK_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.3);
+const BAR_COLOR: Color = Color::PURPLE;
+const FAILED_COLOR: Color = Color::ORANGE_RED;
+const TEXT_COLOR: Color = Color::rgb(0.15, 0.1, 0.15);
+
+/// Root of the screen shown while `GameState::Loading` lasts.
+#[derive(Component)]
+pub struct LoadingScreen;
+
+/// The filled part of the progress bar.
+#[derive(Component)]
+pub struct LoadingBar;
+
+/// What is loading, or which files failed.
+#[derive(Component)]
+pub struct LoadingStatus;
+
+pub fn spawn_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::all(Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    gap: Size::all(Val::Px(12.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(BAR_WIDTH), Val::Px(BAR_HEIGHT)),
+                        ..default()
+                    },
+                    background_color: TRACK_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                                ..default()
+                            },
+                            background_color: BAR_COLOR.into(),
+                            ..default()
+                        },
+                        LoadingBar,
+                    ));
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+                LoadingStatus,
+            ));
+        });
+}
+
+pub fn update_loading_screen(
+    progress: Res<LoadingProgress>,
+    mut bars: Query<(&mut Style, &mut BackgroundColor), With<LoadingBar>>,
+    mut texts: Query<&mut Text, With<LoadingStatus>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+    let failed = !progress.failed.is_empty();
+    for (mut style, mut background_color) in &mut bars {
+        style.size.width = Val::Percent(progress.fraction() * 100.0);
+        background_color.0 = if failed { FAILED_COLOR } else { BAR_COLOR };
+    }
+    for mut text in &mut texts {
+        text.sections[0].value = progress.status();
+    }
+}
+
+/// Shows the status once the manifest has said which file the `fonts.hasklug` key stands for.
+pub fn load_loading_font(
+    asset_server: Res<AssetServer>,
+    dynamic_assets: Res<DynamicAssets>,
+    mut texts: Query<&mut Text, With<LoadingStatus>>,
+) {
+    for mut text in &mut texts {
+        if text.sections[0].style.font != Handle::default() {
+            continue;
+        }
+        let Some(font) = dynamic_assets
+            .get_asset(FONT_KEY)
+            .and_then(|asset| asset.load(&asset_server).into_iter().next())
+        else {
+            continue;
+        };
+        text.sections[0].style.font = font.typed();
+    }
+}
+
+pub fn despawn_loading_screen(mut commands: Commands, screens: Query<Entity, With<LoadingScreen>>) {
+    for screen in &screens {
+        commands.entity(screen).despawn_recursive();
+    }
+}
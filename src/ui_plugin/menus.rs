@@ -0,0 +1,324 @@
+use std::path::PathBuf;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::bindings_plugin::Action;
+use crate::loading_plugin::LoadedAssets;
+use crate::map_plugin::{LoadMap, MapConfig, MapLoadError, NewMap, RecentMaps};
+use crate::GameState;
+
+use super::bindings_menu::BindingsMenu;
+use super::UiConfig;
+
+const BACKDROP_COLOR: Color = Color::rgba(0.93, 0.86, 0.86, 0.85);
+const BUTTON_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.6);
+const HOVERED_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.8);
+const TITLE_COLOR: Color = Color::PURPLE;
+const ERROR_COLOR: Color = Color::MAROON;
+
+/// Root of the screen shown in a menu state, despawned when the state is left.
+#[derive(Component)]
+pub struct MenuScreen;
+
+/// A `UiConfig` switch offered in the settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiSetting {
+    Instructions,
+    StatusBar,
+    Minimap,
+}
+
+impl UiSetting {
+    const ALL: [UiSetting; 3] = [
+        UiSetting::Instructions,
+        UiSetting::StatusBar,
+        UiSetting::Minimap,
+    ];
+
+    fn value(self, config: &mut UiConfig) -> &mut bool {
+        match self {
+            UiSetting::Instructions => &mut config.show_instructions,
+            UiSetting::StatusBar => &mut config.show_status_bar,
+            UiSetting::Minimap => &mut config.show_minimap,
+        }
+    }
+
+    fn label(self, config: &UiConfig) -> String {
+        let (name, shown) = match self {
+            UiSetting::Instructions => ("Instructions", config.show_instructions),
+            UiSetting::StatusBar => ("Status bar", config.show_status_bar),
+            UiSetting::Minimap => ("Minimap", config.show_minimap),
+        };
+        format!("{name}: {}", if shown { "on" } else { "off" })
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub enum MenuButton {
+    NewMap,
+    LoadMap,
+    OpenRecent(PathBuf),
+    Resume,
+    Settings,
+    Toggle(UiSetting),
+    Controls,
+    Back,
+    MainMenu,
+    Quit,
+}
+
+/// The text of a `MenuButton::Toggle`.
+#[derive(Component)]
+pub struct SettingLabel(pub UiSetting);
+
+/// The text under the main menu buttons telling why a map did not load.
+#[derive(Component)]
+pub struct MapErrorText;
+
+/// Where the settings go back to.
+#[derive(Resource)]
+pub struct SettingsReturn(pub GameState);
+
+impl Default for SettingsReturn {
+    fn default() -> Self {
+        Self(GameState::MainMenu)
+    }
+}
+
+/// Spawns a screen-filling backdrop with `title` and a column of buttons filled in by
+/// `buttons`.
+fn spawn_screen(
+    commands: &mut Commands,
+    assets: &LoadedAssets,
+    title: &str,
+    buttons: impl FnOnce(&mut ChildBuilder, &TextStyle),
+) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 20.,
+        color: Color::WHITE,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::all(Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    gap: Size::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: BACKDROP_COLOR.into(),
+                z_index: ZIndex::Global(5),
+                ..default()
+            },
+            // Keeps clicks on the backdrop from reaching the board.
+            Interaction::default(),
+            MenuScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 48.,
+                        color: TITLE_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(16.0)),
+                    ..default()
+                }),
+            );
+            buttons(parent, &text_style);
+        });
+}
+
+fn button_bundle() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::width(Val::Px(260.0)),
+            justify_content: JustifyContent::Center,
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        background_color: BUTTON_COLOR.into(),
+        ..default()
+    }
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    text_style: &TextStyle,
+    label: &str,
+    button: MenuButton,
+) {
+    parent
+        .spawn((button_bundle(), button))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(label, text_style.clone()));
+        });
+}
+
+pub fn spawn_main_menu(
+    mut commands: Commands,
+    assets: Res<LoadedAssets>,
+    recent_maps: Res<RecentMaps>,
+    load_error: Res<MapLoadError>,
+) {
+    spawn_screen(&mut commands, &assets, "hex_tiles", |parent, text_style| {
+        spawn_button(parent, text_style, "New map", MenuButton::NewMap);
+        spawn_button(parent, text_style, "Load map", MenuButton::LoadMap);
+        for path in &recent_maps.0 {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            spawn_button(
+                parent,
+                &TextStyle {
+                    font_size: 16.,
+                    ..text_style.clone()
+                },
+                &name,
+                MenuButton::OpenRecent(path.clone()),
+            );
+        }
+        spawn_button(parent, text_style, "Settings", MenuButton::Settings);
+        spawn_button(parent, text_style, "Quit", MenuButton::Quit);
+        parent.spawn((
+            TextBundle::from_section(
+                load_error.0.clone().unwrap_or_default(),
+                TextStyle {
+                    font_size: 16.,
+                    color: ERROR_COLOR,
+                    ..text_style.clone()
+                },
+            ),
+            MapErrorText,
+        ));
+    });
+}
+
+pub fn spawn_pause_menu(mut commands: Commands, assets: Res<LoadedAssets>) {
+    spawn_screen(&mut commands, &assets, "paused", |parent, text_style| {
+        spawn_button(parent, text_style, "Resume", MenuButton::Resume);
+        spawn_button(parent, text_style, "Settings", MenuButton::Settings);
+        spawn_button(parent, text_style, "Main menu", MenuButton::MainMenu);
+        spawn_button(parent, text_style, "Quit", MenuButton::Quit);
+    });
+}
+
+pub fn spawn_settings(mut commands: Commands, assets: Res<LoadedAssets>, config: Res<UiConfig>) {
+    spawn_screen(&mut commands, &assets, "settings", |parent, text_style| {
+        for setting in UiSetting::ALL {
+            parent
+                .spawn((button_bundle(), MenuButton::Toggle(setting)))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(setting.label(&config), text_style.clone()),
+                        SettingLabel(setting),
+                    ));
+                });
+        }
+        spawn_button(parent, text_style, "Controls", MenuButton::Controls);
+        spawn_button(parent, text_style, "Back", MenuButton::Back);
+    });
+}
+
+pub fn despawn_menu_screens(mut commands: Commands, screens: Query<Entity, With<MenuScreen>>) {
+    for screen in &screens {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+pub fn menu_button_system(
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut settings_return: ResMut<SettingsReturn>,
+    map_config: Res<MapConfig>,
+    mut config: ResMut<UiConfig>,
+    mut new_map: EventWriter<NewMap>,
+    mut load_map: EventWriter<LoadMap>,
+    mut app_exit: EventWriter<AppExit>,
+    mut bindings_menus: Query<&mut Style, With<BindingsMenu>>,
+    mut buttons: Query<(&Interaction, &MenuButton, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, button, mut background_color) in &mut buttons {
+        background_color.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => HOVERED_COLOR,
+        };
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            // The map plugin starts editing once the map is in place.
+            MenuButton::NewMap => new_map.send(NewMap),
+            MenuButton::LoadMap => load_map.send(LoadMap {
+                path: map_config.path.clone(),
+            }),
+            MenuButton::OpenRecent(path) => load_map.send(LoadMap { path: path.clone() }),
+            MenuButton::Resume => next_state.set(GameState::Playing),
+            MenuButton::Settings => {
+                settings_return.0 = state.0.clone();
+                next_state.set(GameState::Settings);
+            }
+            MenuButton::Toggle(setting) => {
+                let value = setting.value(&mut config);
+                *value = !*value;
+            }
+            MenuButton::Controls => {
+                for mut style in &mut bindings_menus {
+                    style.display = Display::Flex;
+                }
+            }
+            MenuButton::Back => next_state.set(settings_return.0.clone()),
+            MenuButton::MainMenu => next_state.set(GameState::MainMenu),
+            MenuButton::Quit => app_exit.send(AppExit),
+        }
+    }
+}
+
+pub fn update_setting_labels(config: Res<UiConfig>, mut labels: Query<(&SettingLabel, &mut Text)>) {
+    if !config.is_changed() {
+        return;
+    }
+    for (label, mut text) in &mut labels {
+        text.sections[0].value = label.0.label(&config);
+    }
+}
+
+pub fn update_map_error_text(
+    load_error: Res<MapLoadError>,
+    mut texts: Query<&mut Text, With<MapErrorText>>,
+) {
+    if !load_error.is_changed() {
+        return;
+    }
+    for mut text in &mut texts {
+        text.sections[0].value = load_error.0.clone().unwrap_or_default();
+    }
+}
+
+/// Pauses and resumes editing, and leaves the settings.
+pub fn pause_system(
+    actions: Res<Input<Action>>,
+    state: Res<State<GameState>>,
+    settings_return: Res<SettingsReturn>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+    match state.0 {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        GameState::Settings => next_state.set(settings_return.0.clone()),
+        GameState::Loading | GameState::MainMenu => {}
+    }
+}
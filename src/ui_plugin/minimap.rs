@@ -0,0 +1,311 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::RelativeCursorPosition;
+use bevy::utils::HashSet;
+use hexx::Hex;
+
+use crate::camera_plugin::CameraFocus;
+use crate::grid_plugin::{GridTracker, MapLoaded, TilePlaced, TileRecolored, TileRemoved};
+
+use super::hud::HudPart;
+
+/// Width and height of the minimap image in pixels.
+const MINIMAP_SIZE: u32 = 160;
+const FRAME_WIDTH: f32 = 3.0;
+const FRAME_COLOR: Color = Color::rgb(0.15, 0.1, 0.15);
+const EMPTY_COLOR: [u8; 4] = [190, 180, 185, 255];
+const FOOTPRINT_COLOR: [u8; 4] = [255, 255, 255, 255];
+/// Farthest a view corner above the horizon is drawn from the camera, in world units.
+const MAX_VIEW_DISTANCE: f32 = 100.0;
+
+/// A top-down picture of the board: each column in its top tile's color, lighter the taller it
+/// is.
+pub struct MinimapCanvas {
+    size: u32,
+    pixels: Vec<[u8; 4]>,
+    /// World position, on the ground plane, of the top left corner.
+    origin: Vec2,
+    /// World units per pixel.
+    scale: f32,
+    /// Number of board hexes when the board was last fitted to the canvas.
+    column_count: usize,
+}
+
+impl MinimapCanvas {
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            pixels: vec![[0; 4]; (size * size) as usize],
+            origin: Vec2::ZERO,
+            scale: 1.0,
+            column_count: 0,
+        }
+    }
+
+    /// Whether the board has grown or shrunk since it was last fitted.
+    pub fn needs_refit(&self, tracker: &GridTracker) -> bool {
+        tracker.columns.len() != self.column_count
+    }
+
+    /// Fits the whole board to the canvas and draws every column.
+    pub fn redraw(&mut self, tracker: &GridTracker) {
+        let radius = tracker.layout.hex_size.max_element();
+        let (min, max) = tracker.columns.keys().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), hex| {
+                let center = tracker.layout.hex_to_world_pos(*hex);
+                (min.min(center), max.max(center))
+            },
+        );
+        let (min, max) = if tracker.columns.is_empty() {
+            (Vec2::ZERO, Vec2::ZERO)
+        } else {
+            (min - radius, max + radius)
+        };
+        let extent = (max - min).max_element().max(radius);
+        self.scale = extent / self.size as f32;
+        // Center the board along its shorter side.
+        self.origin = (min + max) / 2.0 - extent / 2.0;
+        self.column_count = tracker.columns.len();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let hex = self.hex_at(x, y, tracker);
+                self.pixels[(y * self.size + x) as usize] = column_color(tracker, hex);
+            }
+        }
+    }
+
+    /// Redraws the pixels of `hexes` only.
+    pub fn draw_hexes(&mut self, tracker: &GridTracker, hexes: impl IntoIterator<Item = Hex>) {
+        let radius = tracker.layout.hex_size.max_element() / self.scale + 1.0;
+        for hex in hexes {
+            let center = self.world_to_pixel(tracker.layout.hex_to_world_pos(hex));
+            let color = column_color(tracker, hex);
+            let (min, max) = (center - radius, center + radius);
+            let clamp = |value: f32| (value.max(0.0) as u32).min(self.size);
+            for y in clamp(min.y)..clamp(max.y) {
+                for x in clamp(min.x)..clamp(max.x) {
+                    if self.hex_at(x, y, tracker) == hex {
+                        self.pixels[(y * self.size + x) as usize] = color;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The color of pixel `x`, `y`, transparent outside the board.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        self.pixels[(y * self.size + x) as usize]
+    }
+
+    /// Where a point of the ground plane, as `(x, z)`, is drawn.
+    pub fn world_to_pixel(&self, position: Vec2) -> Vec2 {
+        (position - self.origin) / self.scale
+    }
+
+    pub fn pixel_to_world(&self, pixel: Vec2) -> Vec2 {
+        self.origin + pixel * self.scale
+    }
+
+    /// The board with the outline of `footprint`, given on the ground plane, drawn over it, as
+    /// RGBA bytes.
+    pub fn compose(&self, footprint: Option<[Vec2; 4]>) -> Vec<u8> {
+        let mut pixels = self.pixels.clone();
+        if let Some(corners) = footprint {
+            let corners = corners.map(|corner| self.world_to_pixel(corner));
+            for (index, start) in corners.iter().enumerate() {
+                let end = corners[(index + 1) % corners.len()];
+                self.draw_line(&mut pixels, *start, end);
+            }
+        }
+        pixels.into_iter().flatten().collect()
+    }
+
+    fn draw_line(&self, pixels: &mut [[u8; 4]], start: Vec2, end: Vec2) {
+        // Corners far off the canvas are pulled in to bound the steps taken; the visible part
+        // of the line barely moves.
+        let bound = Vec2::splat(self.size as f32 * 4.0);
+        let (start, end) = (start.clamp(-bound, bound), end.clamp(-bound, bound));
+        let steps = (end - start).abs().max_element().ceil().max(1.0) as u32;
+        for step in 0..=steps {
+            let point = start.lerp(end, step as f32 / steps as f32);
+            if point.x >= 0.0
+                && point.y >= 0.0
+                && point.x < self.size as f32
+                && point.y < self.size as f32
+            {
+                pixels[(point.y as u32 * self.size + point.x as u32) as usize] = FOOTPRINT_COLOR;
+            }
+        }
+    }
+
+    fn hex_at(&self, x: u32, y: u32, tracker: &GridTracker) -> Hex {
+        let position = self.pixel_to_world(Vec2::new(x as f32 + 0.5, y as f32 + 0.5));
+        tracker.layout.world_pos_to_hex(position)
+    }
+}
+
+fn column_color(tracker: &GridTracker, hex: Hex) -> [u8; 4] {
+    let Some(column) = tracker.columns.get(&hex) else {
+        return [0; 4];
+    };
+    let Some(top) = column.last() else {
+        return EMPTY_COLOR;
+    };
+    let height = column.len() as f32;
+    // Approaches full brightness as columns grow, without depending on the tallest one.
+    let brightness = 0.5 + 0.5 * height / (height + 4.0);
+    let [r, g, b, _] = Color::from(top.variant.clone()).as_rgba_f32();
+    let [r, g, b] = [r, g, b].map(|channel| (channel * brightness * 255.0).round() as u8);
+    [r, g, b, 255]
+}
+
+/// The minimap image and what it was drawn from.
+#[derive(Resource)]
+pub struct Minimap {
+    pub image: Handle<Image>,
+    pub canvas: MinimapCanvas,
+    footprint: Option<[Vec2; 4]>,
+}
+
+/// The minimap in the corner of the screen; clicking it moves the camera focus there.
+#[derive(Component)]
+pub struct MinimapView;
+
+pub fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = images.add(Image::new_fill(
+        Extent3d {
+            width: MINIMAP_SIZE,
+            height: MINIMAP_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+    ));
+    commands.insert_resource(Minimap {
+        image: image.clone(),
+        canvas: MinimapCanvas::new(MINIMAP_SIZE),
+        footprint: None,
+    });
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        right: Val::Px(8.0),
+                        top: Val::Px(8.0),
+                        ..default()
+                    },
+                    padding: UiRect::all(Val::Px(FRAME_WIDTH)),
+                    ..default()
+                },
+                background_color: FRAME_COLOR.into(),
+                ..default()
+            },
+            HudPart::Minimap,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageBundle {
+                    style: Style {
+                        size: Size::all(Val::Px(MINIMAP_SIZE as f32)),
+                        ..default()
+                    },
+                    image: image.into(),
+                    ..default()
+                },
+                Interaction::default(),
+                RelativeCursorPosition::default(),
+                MinimapView,
+            ));
+        });
+}
+
+/// Redraws the columns edited since the last frame, or the whole board after loading or growing,
+/// and the outline of what the camera sees.
+pub fn update_minimap(
+    tracker: Res<GridTracker>,
+    minimap: Option<ResMut<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut placed: EventReader<TilePlaced>,
+    mut removed: EventReader<TileRemoved>,
+    mut recolored: EventReader<TileRecolored>,
+    mut loaded: EventReader<MapLoaded>,
+) {
+    let Some(mut minimap) = minimap else {
+        return;
+    };
+    let edited: HashSet<Hex> = placed
+        .iter()
+        .map(|event| event.hex)
+        .chain(removed.iter().map(|event| event.hex))
+        .chain(recolored.iter().map(|event| event.hex))
+        .collect();
+    let mut dirty = true;
+    if loaded.iter().count() > 0 || minimap.canvas.needs_refit(&tracker) {
+        minimap.canvas.redraw(&tracker);
+    } else if !edited.is_empty() {
+        minimap.canvas.draw_hexes(&tracker, edited);
+    } else {
+        dirty = false;
+    }
+
+    let footprint = cameras
+        .iter()
+        .find(|(camera, _)| camera.is_active)
+        .and_then(|(camera, transform)| view_footprint(camera, transform));
+    if !dirty && footprint == minimap.footprint {
+        return;
+    }
+    minimap.footprint = footprint;
+    if let Some(image) = images.get_mut(&minimap.image) {
+        image.data = minimap.canvas.compose(footprint);
+    }
+}
+
+/// Where the corners of the view meet the ground plane, as `(x, z)`.
+fn view_footprint(camera: &Camera, transform: &GlobalTransform) -> Option<[Vec2; 4]> {
+    let size = camera.logical_viewport_size()?;
+    let corners = [
+        Vec2::ZERO,
+        Vec2::new(size.x, 0.0),
+        size,
+        Vec2::new(0.0, size.y),
+    ];
+    let mut footprint = [Vec2::ZERO; 4];
+    for (point, corner) in footprint.iter_mut().zip(corners) {
+        let ray = camera.viewport_to_world(transform, corner)?;
+        let distance = match ray.intersect_plane(Vec3::ZERO, Vec3::Y) {
+            Some(distance) => distance.min(MAX_VIEW_DISTANCE),
+            None => MAX_VIEW_DISTANCE,
+        };
+        let ground = ray.get_point(distance);
+        *point = Vec2::new(ground.x, ground.z);
+    }
+    Some(footprint)
+}
+
+pub fn minimap_click_system(
+    minimap: Option<Res<Minimap>>,
+    views: Query<(&Interaction, &RelativeCursorPosition), With<MinimapView>>,
+    mut focus: ResMut<CameraFocus>,
+) {
+    let Some(minimap) = minimap else {
+        return;
+    };
+    for (interaction, cursor) in &views {
+        let Some(position) = cursor
+            .normalized
+            .filter(|_| *interaction == Interaction::Clicked)
+        else {
+            continue;
+        };
+        let ground = minimap
+            .canvas
+            .pixel_to_world(position * MINIMAP_SIZE as f32);
+        focus.target = Vec3::new(ground.x, focus.target.y, ground.y);
+    }
+}
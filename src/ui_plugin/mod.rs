@@ -1,16 +1,140 @@
+mod bindings_menu;
+mod color_picker;
+mod hud;
+mod loading_screen;
+mod menus;
+mod minimap;
 mod setup;
+mod status_bar;
 mod tile_select;
+mod toolbar;
 
 use bevy::prelude::*;
+use bevy_asset_loader::prelude::*;
 
+use crate::loading_plugin::LoadedAssets;
 use crate::GameState;
 
-use self::{setup::setup, tile_select::tile_select_system};
+use self::bindings_menu::{
+    bindings_menu_system, hide_bindings_menu, spawn_bindings_menu, toggle_bindings_menu,
+};
+pub use self::color_picker::ColorPicker;
+use self::color_picker::{
+    add_color_variant, color_slider_system, hex_field_system, spawn_color_picker,
+    toggle_color_picker, update_color_picker,
+};
+use self::hud::update_hud_visibility;
+use self::loading_screen::{
+    despawn_loading_screen, load_loading_font, spawn_loading_screen, update_loading_screen,
+};
+use self::menus::{
+    despawn_menu_screens, menu_button_system, pause_system, spawn_main_menu, spawn_pause_menu,
+    spawn_settings, update_map_error_text, update_setting_labels, SettingsReturn,
+};
+use self::minimap::{minimap_click_system, spawn_minimap, update_minimap};
+pub use self::minimap::{Minimap, MinimapCanvas};
+use self::setup::setup;
+pub use self::status_bar::status_line;
+use self::status_bar::{spawn_status_bar, status_bar_system};
+use self::tile_select::{highlight_selected_variant, rebuild_palette, tile_select_system};
+pub use self::toolbar::ToolIcons;
+use self::toolbar::{spawn_toolbar, toolbar_system, update_tool_hotkeys};
+
+#[derive(Resource, Clone, Debug)]
+pub struct UiConfig {
+    pub show_instructions: bool,
+    /// Show the hovered hex, tile count and active tool along the bottom of the screen.
+    pub show_status_bar: bool,
+    /// Show a top-down map of the board in the top right corner.
+    pub show_minimap: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            show_instructions: true,
+            show_status_bar: true,
+            show_minimap: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UiPlugin {
+    pub config: UiConfig,
+}
 
-pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(setup.in_schedule(OnEnter(GameState::Playing)))
-            .add_system(tile_select_system.in_set(OnUpdate(GameState::Playing)));
+        app.insert_resource(self.config.clone())
+            .add_collection_to_loading_state::<_, LoadedAssets>(GameState::Loading)
+            .add_collection_to_loading_state::<_, ToolIcons>(GameState::Loading)
+            .init_resource::<ColorPicker>()
+            .init_resource::<SettingsReturn>()
+            .add_system(spawn_loading_screen.in_schedule(OnEnter(GameState::Loading)))
+            .add_systems(
+                (load_loading_font, update_loading_screen).in_set(OnUpdate(GameState::Loading)),
+            )
+            .add_system(despawn_loading_screen.in_schedule(OnExit(GameState::Loading)))
+            // Spawned once, hidden outside the editor by `update_hud_visibility`.
+            .add_systems(
+                (
+                    setup,
+                    spawn_toolbar,
+                    spawn_status_bar,
+                    spawn_bindings_menu,
+                    spawn_color_picker,
+                    spawn_minimap,
+                )
+                    .in_schedule(OnExit(GameState::Loading)),
+            )
+            .add_system(spawn_main_menu.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(spawn_pause_menu.in_schedule(OnEnter(GameState::Paused)))
+            .add_system(spawn_settings.in_schedule(OnEnter(GameState::Settings)))
+            .add_system(hide_bindings_menu.in_schedule(OnExit(GameState::Playing)))
+            .add_system(despawn_menu_screens.in_schedule(OnExit(GameState::MainMenu)))
+            .add_system(despawn_menu_screens.in_schedule(OnExit(GameState::Paused)))
+            .add_systems(
+                (despawn_menu_screens, hide_bindings_menu).in_schedule(OnExit(GameState::Settings)),
+            )
+            .add_systems((
+                update_hud_visibility,
+                pause_system,
+                menu_button_system,
+                update_setting_labels,
+                update_map_error_text,
+            ))
+            .add_systems(
+                (toggle_bindings_menu, bindings_menu_system).distributive_run_if(
+                    |state: Res<State<GameState>>| {
+                        matches!(state.0, GameState::Playing | GameState::Settings)
+                    },
+                ),
+            )
+            .add_systems(
+                (
+                    rebuild_palette,
+                    tile_select_system,
+                    highlight_selected_variant,
+                    toolbar_system,
+                    update_tool_hotkeys,
+                    status_bar_system,
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_systems(
+                (
+                    toggle_color_picker,
+                    color_slider_system,
+                    hex_field_system,
+                    update_color_picker,
+                    add_color_variant,
+                )
+                    .chain()
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_systems(
+                (update_minimap, minimap_click_system).in_set(OnUpdate(GameState::Playing)),
+            );
     }
 }
@@ -1,29 +1,35 @@
 use bevy::prelude::*;
 use bevy::ui::FocusPolicy;
-use strum::IntoEnumIterator;
 
-use crate::grid_plugin::TileVariant;
 use crate::loading_plugin::LoadedAssets;
 
+use super::hud::HudPart;
+use super::status_bar::STATUS_BAR_HEIGHT;
+use super::tile_select::{PaletteBar, ENTRY_GAP};
+
 pub fn setup(mut commands: Commands, assets: Res<LoadedAssets>) {
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                size: Size::width(Val::Percent(100.0)),
-                justify_content: JustifyContent::SpaceBetween,
-                flex_direction: FlexDirection::Column,
-                padding: UiRect::new(
-                    Val::Percent(2.0),
-                    Val::Percent(2.0),
-                    Val::Percent(1.0),
-                    Val::Percent(2.0),
-                ),
-                gap: Size::all(Val::Percent(2.0)),
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::width(Val::Percent(100.0)),
+                    justify_content: JustifyContent::SpaceBetween,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::new(
+                        Val::Percent(2.0),
+                        Val::Percent(2.0),
+                        Val::Percent(1.0),
+                        // Keep the palette clear of the status bar.
+                        Val::Px(STATUS_BAR_HEIGHT + 8.0),
+                    ),
+                    gap: Size::all(Val::Percent(2.0)),
+                    ..default()
+                },
+                focus_policy: FocusPolicy::Pass,
                 ..default()
             },
-            focus_policy: FocusPolicy::Pass,
-            ..default()
-        })
+            HudPart::Always,
+        ))
         .with_children(|parent| {
             parent
                 .spawn(NodeBundle {
@@ -52,17 +58,24 @@ pub fn setup(mut commands: Commands, assets: Res<LoadedAssets>) {
                                 },
                             ));
                             parent
-                                .spawn(NodeBundle {
-                                    style: Style {
-                                        padding: UiRect::all(Val::Px(6.0)),
+                                .spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            padding: UiRect::all(Val::Px(6.0)),
+                                            ..default()
+                                        },
                                         ..default()
                                     },
-                                    ..default()
-                                })
+                                    HudPart::Instructions,
+                                ))
                                 .with_children(|parent| {
                                     parent.spawn(
                                         TextBundle::from_section(
-                                            "arrow keys to orbit camera.\nleft click to add tiles.",
+                                            "arrow keys to orbit camera.\n\
+                                             left click to use the tool.\n\
+                                             F10 to change controls.\n\
+                                             esc to pause.\n\
+                                             drop a palette file to import colors.",
                                             TextStyle {
                                                 font: assets.font.clone(),
                                                 font_size: 18.,
@@ -79,30 +92,17 @@ pub fn setup(mut commands: Commands, assets: Res<LoadedAssets>) {
                                 });
                         });
                 });
-            parent
-                .spawn(NodeBundle {
+            parent.spawn((
+                NodeBundle {
                     style: Style {
                         size: Size::new(Val::Percent(100.0), Val::Percent(10.0)),
-                        flex_direction: FlexDirection::RowReverse,
-                        gap: Size::all(Val::Percent(1.0)),
+                        justify_content: JustifyContent::FlexEnd,
+                        gap: Size::all(Val::Percent(ENTRY_GAP)),
                         ..default()
                     },
                     ..default()
-                })
-                .with_children(|parent| {
-                    TileVariant::iter().for_each(|palette_tile| {
-                        parent.spawn((
-                            ButtonBundle {
-                                style: Style {
-                                    size: Size::new(Val::Percent(6.0), Val::Percent(95.0)),
-                                    ..default()
-                                },
-                                background_color: Color::from(palette_tile.clone()).into(),
-                                ..default()
-                            },
-                            palette_tile,
-                        ));
-                    })
-                });
+                },
+                PaletteBar,
+            ));
         });
 }
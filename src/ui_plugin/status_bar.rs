@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use hexx::{Hex, OffsetHexMode};
+
+use crate::grid_plugin::{ActiveTool, EditorTools, GridTracker, HoveredHex};
+use crate::loading_plugin::LoadedAssets;
+
+use super::hud::HudPart;
+
+/// Height of the bar in pixels.
+pub const STATUS_BAR_HEIGHT: f32 = 20.;
+
+/// The text of the status bar along the bottom of the screen.
+#[derive(Component)]
+pub struct StatusText;
+
+pub fn spawn_status_bar(mut commands: Commands, assets: Res<LoadedAssets>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(0.0),
+                        bottom: Val::Px(0.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Percent(100.0), Val::Px(STATUS_BAR_HEIGHT)),
+                    align_items: AlignItems::Center,
+                    padding: UiRect::horizontal(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.15, 0.1, 0.15, 0.7).into(),
+                ..default()
+            },
+            HudPart::StatusBar,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 14.,
+                        color: Color::WHITE,
+                    },
+                ),
+                StatusText,
+            ));
+        });
+}
+
+pub fn status_bar_system(
+    hovered: Res<HoveredHex>,
+    tracker: Res<GridTracker>,
+    active: Res<ActiveTool>,
+    tools: Res<EditorTools>,
+    mut texts: Query<&mut Text, With<StatusText>>,
+) {
+    if !hovered.is_changed() && !tracker.is_changed() && !active.is_changed() {
+        return;
+    }
+    let tool = tools
+        .iter()
+        .find(|(tool, _)| *tool == active.tool)
+        .map_or("none", |(_, name)| name);
+    let status = status_line(hovered.0.map(|hit| hit.hex), &tracker, tool);
+    for mut text in &mut texts {
+        text.sections[0].value = status.clone();
+    }
+}
+
+/// The text of the status bar: where `hovered` is and what it holds, the tile count and `tool`.
+pub fn status_line(hovered: Option<Hex>, tracker: &GridTracker, tool: &str) -> String {
+    let hex = hovered.map_or_else(
+        || "no hex".to_string(),
+        |hex| {
+            // The board is pointy topped, so offset coordinates shift alternate rows.
+            let [column, row] = hex.to_offset_coordinates(OffsetHexMode::OddRows);
+            let column_tiles = tracker.columns.get(&hex).map_or(&[][..], Vec::as_slice);
+            let top = column_tiles
+                .last()
+                .map_or("empty".to_string(), |tile| tile.variant.to_string());
+            format!(
+                "axial ({}, {})   cube ({}, {}, {})   offset ({column}, {row})   \
+                 height {}   top {top}",
+                hex.x,
+                hex.y,
+                hex.x,
+                hex.y,
+                hex.z(),
+                column_tiles.len(),
+            )
+        },
+    );
+    format!("{hex}   |   {} tiles   |   {tool}", tracker.tiles.len())
+}
@@ -1,51 +1,160 @@
 use bevy::prelude::*;
 
-use crate::grid_plugin::{GridTracker, TileVariant};
+use crate::grid_plugin::{GridTracker, Palette, TileVariant};
+use crate::loading_plugin::LoadedAssets;
+
+use super::color_picker::AddColorButton;
+
+/// Color of the frame around the selected variant.
+const SELECTED_BORDER: Color = Color::rgb(0.15, 0.1, 0.15);
+const BORDER_WIDTH: f32 = 3.0;
+/// Space between palette buttons, in percent of the row.
+pub const ENTRY_GAP: f32 = 1.0;
+/// Width of a palette button, in percent of the row, so a full palette and the button adding to
+/// it fill the row.
+const ENTRY_WIDTH: f32 =
+    (100.0 - ENTRY_GAP * Palette::CAPACITY as f32) / (Palette::CAPACITY + 1) as f32;
+
+/// Row at the bottom of the screen holding one button per palette entry.
+#[derive(Component)]
+pub struct PaletteBar;
+
+/// A palette button. Its background is the border shown while the variant is selected; the
+/// `PaletteSwatch` inside shows the variant's color.
+#[derive(Component)]
+pub struct PaletteEntry(pub TileVariant);
+
+#[derive(Component)]
+pub struct PaletteSwatch;
+
+/// Respawns the palette buttons whenever the palette changes, followed by the button opening the
+/// color picker.
+pub fn rebuild_palette(
+    mut commands: Commands,
+    palette: Res<Palette>,
+    assets: Res<LoadedAssets>,
+    bars: Query<Entity, With<PaletteBar>>,
+    new_bars: Query<(), Added<PaletteBar>>,
+) {
+    if !palette.is_changed() && new_bars.is_empty() {
+        return;
+    }
+    for bar in &bars {
+        commands.entity(bar).despawn_descendants();
+        commands.entity(bar).with_children(|parent| {
+            for (index, variant) in palette.0.iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(ENTRY_WIDTH), Val::Percent(95.0)),
+                                padding: UiRect::all(Val::Px(BORDER_WIDTH)),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        PaletteEntry(variant.clone()),
+                    ))
+                    .with_children(|parent| {
+                        parent
+                            .spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        size: Size::all(Val::Percent(100.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    background_color: Color::from(variant.clone()).into(),
+                                    ..default()
+                                },
+                                PaletteSwatch,
+                            ))
+                            .with_children(|parent| {
+                                if index < 9 {
+                                    parent.spawn(TextBundle::from_section(
+                                        (index + 1).to_string(),
+                                        TextStyle {
+                                            font: assets.font.clone(),
+                                            font_size: 14.,
+                                            color: Color::WHITE,
+                                        },
+                                    ));
+                                }
+                            });
+                    });
+            }
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(ENTRY_WIDTH), Val::Percent(95.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.15, 0.1, 0.15, 0.6).into(),
+                        ..default()
+                    },
+                    AddColorButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "+",
+                        TextStyle {
+                            font: assets.font.clone(),
+                            font_size: 24.,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+    }
+}
 
 pub fn tile_select_system(
-    mut interactions: Query<
-        (&Interaction, &TileVariant, &mut BackgroundColor),
-        (Changed<Interaction>, With<Button>),
-    >,
+    interactions: Query<(&Interaction, &PaletteEntry, &Children), Changed<Interaction>>,
+    mut swatches: Query<&mut BackgroundColor, With<PaletteSwatch>>,
     mut world_tracker: ResMut<GridTracker>,
 ) {
-    for (interaction, tile_variant, mut background_color) in &mut interactions {
-        match *interaction {
+    for (interaction, entry, children) in &interactions {
+        let base = Color::from(entry.0.clone());
+        let color = match *interaction {
             Interaction::Clicked => {
-                world_tracker.current_tile_variant = tile_variant.clone();
-            }
-            Interaction::Hovered => {
-                if let Color::Hsla {
-                    hue,
-                    saturation,
-                    lightness,
-                    alpha,
-                } = Color::from(tile_variant.clone()).as_hsla()
-                {
-                    background_color.0 = Color::Hsla {
-                        hue,
-                        saturation,
-                        lightness: lightness + 0.05,
-                        alpha,
-                    };
-                };
+                world_tracker.current_tile_variant = entry.0.clone();
+                shade(base, -0.1)
             }
-            Interaction::None => {
-                if let Color::Hsla {
-                    hue,
-                    saturation,
-                    lightness,
-                    alpha,
-                } = Color::from(tile_variant.clone()).as_hsla()
-                {
-                    background_color.0 = Color::Hsla {
-                        hue,
-                        saturation,
-                        lightness: lightness - 0.05,
-                        alpha,
-                    };
-                };
+            Interaction::Hovered => shade(base, 0.1),
+            Interaction::None => base,
+        };
+        for child in children {
+            if let Ok(mut background_color) = swatches.get_mut(*child) {
+                background_color.0 = color;
             }
         }
     }
 }
+
+/// Frames the button of the variant that is placed next, however it was selected.
+pub fn highlight_selected_variant(
+    tracker: Res<GridTracker>,
+    mut entries: Query<(&PaletteEntry, &mut BackgroundColor)>,
+) {
+    for (entry, mut background_color) in &mut entries {
+        let color = if entry.0 == tracker.current_tile_variant {
+            SELECTED_BORDER
+        } else {
+            Color::NONE
+        };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+}
+
+/// `color` with its lightness moved by `amount`.
+fn shade(color: Color, amount: f32) -> Color {
+    let [hue, saturation, lightness, alpha] = color.as_hsla_f32();
+    Color::hsla(hue, saturation, (lightness + amount).clamp(0.0, 1.0), alpha)
+}
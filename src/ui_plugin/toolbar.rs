@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use bevy_asset_loader::prelude::*;
+
+use crate::bindings_plugin::{Action, InputBindings};
+use crate::grid_plugin::{ActiveTool, EditorTools, Tool};
+use crate::loading_plugin::LoadedAssets;
+
+use super::hud::HudPart;
+
+const BUTTON_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.6);
+const HOVERED_COLOR: Color = Color::rgba(0.15, 0.1, 0.15, 0.8);
+const ACTIVE_COLOR: Color = Color::PURPLE;
+
+#[derive(AssetCollection, Resource)]
+pub struct ToolIcons {
+    #[asset(key = "icons.build")]
+    pub build: Handle<Image>,
+    #[asset(key = "icons.erase")]
+    pub erase: Handle<Image>,
+    #[asset(key = "icons.paint")]
+    pub paint: Handle<Image>,
+    #[asset(key = "icons.fill")]
+    pub fill: Handle<Image>,
+    #[asset(key = "icons.select")]
+    pub select: Handle<Image>,
+    #[asset(key = "icons.eyedropper")]
+    pub eyedropper: Handle<Image>,
+}
+
+impl ToolIcons {
+    /// The icon of a built-in tool.
+    pub fn get(&self, tool: Tool) -> Option<Handle<Image>> {
+        match tool {
+            Tool::Build => Some(self.build.clone()),
+            Tool::Erase => Some(self.erase.clone()),
+            Tool::Paint => Some(self.paint.clone()),
+            Tool::Fill => Some(self.fill.clone()),
+            Tool::Select => Some(self.select.clone()),
+            Tool::Eyedropper => Some(self.eyedropper.clone()),
+            Tool::Custom(_) => None,
+        }
+    }
+}
+
+/// A tool bar button, switching to the tool when clicked.
+#[derive(Component)]
+pub struct ToolButton(pub Tool);
+
+/// The hotkey label of a tool bar button.
+#[derive(Component)]
+pub struct ToolHotkey(pub Tool);
+
+pub fn spawn_toolbar(
+    mut commands: Commands,
+    assets: Res<LoadedAssets>,
+    icons: Res<ToolIcons>,
+    tools: Res<EditorTools>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(8.0),
+                        top: Val::Percent(20.0),
+                        ..default()
+                    },
+                    flex_direction: FlexDirection::Column,
+                    gap: Size::all(Val::Px(4.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            HudPart::Always,
+        ))
+        .with_children(|parent| {
+            for (tool, name) in tools.iter() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::all(Val::Px(40.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: BUTTON_COLOR.into(),
+                            ..default()
+                        },
+                        ToolButton(tool),
+                    ))
+                    .with_children(|parent| {
+                        // Tools without an icon show their initial instead.
+                        match icons.get(tool) {
+                            Some(icon) => parent.spawn(ImageBundle {
+                                style: Style {
+                                    size: Size::all(Val::Px(28.0)),
+                                    ..default()
+                                },
+                                image: icon.into(),
+                                ..default()
+                            }),
+                            None => parent.spawn(TextBundle::from_section(
+                                name.chars().next().unwrap_or('?').to_string(),
+                                TextStyle {
+                                    font: assets.font.clone(),
+                                    font_size: 24.,
+                                    color: Color::WHITE,
+                                },
+                            )),
+                        };
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: assets.font.clone(),
+                                    font_size: 11.,
+                                    color: Color::WHITE,
+                                },
+                            )
+                            .with_style(Style {
+                                position_type: PositionType::Absolute,
+                                position: UiRect {
+                                    right: Val::Px(2.0),
+                                    bottom: Val::Px(0.0),
+                                    ..default()
+                                },
+                                ..default()
+                            }),
+                            ToolHotkey(tool),
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn toolbar_system(
+    mut active: ResMut<ActiveTool>,
+    mut buttons: Query<(&Interaction, &ToolButton, &mut BackgroundColor)>,
+) {
+    for (interaction, button, _) in &buttons {
+        if *interaction == Interaction::Clicked {
+            active.set(button.0);
+        }
+    }
+    for (interaction, button, mut background_color) in &mut buttons {
+        let color = match interaction {
+            _ if button.0 == active.tool => ACTIVE_COLOR,
+            Interaction::Hovered | Interaction::Clicked => HOVERED_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+}
+
+/// Shows the first key bound to each tool, following rebinding.
+pub fn update_tool_hotkeys(
+    bindings: Res<InputBindings>,
+    mut labels: Query<(&ToolHotkey, &mut Text)>,
+) {
+    if !bindings.is_changed() {
+        return;
+    }
+    for (label, mut text) in &mut labels {
+        text.sections[0].value = bindings
+            .get(Action::UseTool(label.0))
+            .first()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+    }
+}
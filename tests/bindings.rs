@@ -0,0 +1,210 @@
+mod common;
+
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_app::grid_plugin::{EditHistory, HexCursor, Palette};
+use bevy_app::{
+    Action, Binding, GridTracker, InputBindings, InputButton, Modifier, Rebinding, TextInputFocus,
+    TileVariant, Tool,
+};
+use common::{headless_app, key, tap};
+use hexx::Hex;
+
+fn tap_with(app: &mut App, modifier: KeyCode, key_code: KeyCode) {
+    key(app, modifier, ButtonState::Pressed);
+    tap(app, key_code);
+    key(app, modifier, ButtonState::Released);
+}
+
+fn height(app: &App, hex: Hex) -> Option<u32> {
+    app.world.resource::<GridTracker>().height(hex)
+}
+
+#[test]
+fn default_bindings_have_no_conflicts() {
+    assert_eq!(InputBindings::default().conflicts(), Vec::new());
+}
+
+#[test]
+fn every_action_has_a_default_binding() {
+    let bindings = InputBindings::default();
+    for action in Action::all() {
+        assert!(!bindings.get(action).is_empty(), "{action} is unbound");
+    }
+}
+
+#[test]
+fn rebinding_reports_conflicts() {
+    let mut bindings = InputBindings::default();
+    let conflicts = bindings.rebind(Action::Undo, Binding::new(InputButton::Key(KeyCode::X)));
+
+    assert_eq!(conflicts, vec![Action::Erase]);
+    assert_eq!(bindings.conflicts().len(), 1);
+}
+
+#[test]
+fn settings_round_trip_and_fill_in_missing_actions() {
+    let mut bindings = InputBindings::default();
+    bindings.rebind(
+        Action::Place,
+        Binding::with(Modifier::Alt, InputButton::Key(KeyCode::P)),
+    );
+    assert_eq!(
+        InputBindings::from_ron(&bindings.to_ron().unwrap()).unwrap(),
+        bindings
+    );
+
+    let partial = InputBindings::from_ron("(bindings: {Undo: [(input: Key(U))]})").unwrap();
+    assert_eq!(
+        partial.get(Action::Undo),
+        &[Binding::new(InputButton::Key(KeyCode::U))]
+    );
+    assert_eq!(
+        partial.get(Action::Erase),
+        InputBindings::default().get(Action::Erase)
+    );
+}
+
+#[test]
+fn modifiers_must_match_exactly() {
+    let mut app = headless_app();
+    tap(&mut app, KeyCode::D);
+    let hex = app.world.resource::<HexCursor>().hex.unwrap();
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(height(&app, hex), Some(1));
+
+    // Ctrl+Z undoes instead of moving the cursor down left.
+    tap_with(&mut app, KeyCode::LControl, KeyCode::Z);
+    assert_eq!(app.world.resource::<HexCursor>().hex, Some(hex));
+    assert_eq!(height(&app, hex), Some(0));
+}
+
+#[test]
+fn undo_reverts_placements_and_erasures_in_order() {
+    let mut app = headless_app();
+    tap(&mut app, KeyCode::Q);
+    let hex = app.world.resource::<HexCursor>().hex.unwrap();
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::Tab);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::X);
+    assert_eq!(height(&app, hex), Some(1));
+    assert_eq!(app.world.resource::<EditHistory>().steps.len(), 3);
+
+    tap_with(&mut app, KeyCode::LControl, KeyCode::Z);
+    assert_eq!(height(&app, hex), Some(2));
+    assert_eq!(
+        app.world.resource::<GridTracker>().columns[&hex][1].variant,
+        TileVariant::Orange
+    );
+
+    tap_with(&mut app, KeyCode::LControl, KeyCode::Z);
+    tap_with(&mut app, KeyCode::LControl, KeyCode::Z);
+    assert_eq!(height(&app, hex), Some(0));
+    assert!(app.world.resource::<EditHistory>().steps.is_empty());
+
+    // Nothing left to undo.
+    tap_with(&mut app, KeyCode::LControl, KeyCode::Z);
+    assert_eq!(height(&app, hex), Some(0));
+}
+
+#[test]
+fn number_keys_select_palette_entries() {
+    let mut app = headless_app();
+    let variant = |app: &App| {
+        app.world
+            .resource::<GridTracker>()
+            .current_tile_variant
+            .clone()
+    };
+    tap(&mut app, KeyCode::Key3);
+    assert_eq!(variant(&app), TileVariant::Orange);
+    tap(&mut app, KeyCode::Key1);
+    assert_eq!(variant(&app), TileVariant::Cyan);
+    // There is no ninth variant.
+    tap(&mut app, KeyCode::Key9);
+    assert_eq!(variant(&app), TileVariant::Cyan);
+}
+
+#[test]
+fn number_keys_follow_the_palette_order() {
+    let mut app = headless_app();
+    app.world
+        .insert_resource(Palette(vec![TileVariant::Orange, TileVariant::Cyan]));
+    tap(&mut app, KeyCode::Key1);
+    assert_eq!(
+        app.world.resource::<GridTracker>().current_tile_variant,
+        TileVariant::Orange
+    );
+    tap(&mut app, KeyCode::Tab);
+    assert_eq!(
+        app.world.resource::<GridTracker>().current_tile_variant,
+        TileVariant::Cyan
+    );
+}
+
+#[test]
+fn key_bindings_are_suspended_while_typing() {
+    let mut app = headless_app();
+    app.world.resource_mut::<TextInputFocus>().0 = true;
+    tap(&mut app, KeyCode::Key3);
+    assert_eq!(
+        app.world.resource::<GridTracker>().current_tile_variant,
+        TileVariant::Purple
+    );
+
+    app.world.resource_mut::<TextInputFocus>().0 = false;
+    app.update();
+    tap(&mut app, KeyCode::Key3);
+    assert_eq!(
+        app.world.resource::<GridTracker>().current_tile_variant,
+        TileVariant::Orange
+    );
+}
+
+#[test]
+fn captured_input_replaces_the_binding_of_its_device() {
+    let mut app = headless_app();
+    app.world.resource_mut::<Rebinding>().action = Some(Action::Place);
+    key(&mut app, KeyCode::K, ButtonState::Pressed);
+    // The key that was captured does not trigger its new action until pressed again.
+    assert!(!app.world.resource::<Input<Action>>().pressed(Action::Place));
+    key(&mut app, KeyCode::K, ButtonState::Released);
+
+    let rebinding = app.world.resource::<Rebinding>();
+    assert_eq!(rebinding.action, None);
+    assert!(rebinding.conflicts.is_empty());
+    assert_eq!(
+        app.world.resource::<InputBindings>().get(Action::Place),
+        &[
+            Binding::new(InputButton::Mouse(MouseButton::Left)),
+            Binding::new(InputButton::Gamepad(GamepadButtonType::South)),
+            Binding::new(InputButton::Key(KeyCode::K)),
+        ]
+    );
+
+    tap(&mut app, KeyCode::D);
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(
+        height(&app, app.world.resource::<HexCursor>().hex.unwrap()),
+        Some(0)
+    );
+    let hex = app.world.resource::<HexCursor>().hex.unwrap();
+    tap(&mut app, KeyCode::K);
+    assert_eq!(height(&app, hex), Some(1));
+}
+
+#[test]
+fn custom_tools_are_listed_and_checked_for_conflicts() {
+    let actions = Action::with_tools(Tool::BUILT_IN.into_iter().chain([Tool::Custom(0)]));
+    assert!(actions.contains(&Action::UseTool(Tool::Custom(0))));
+    assert_eq!(actions.len(), Action::all().len() + 1);
+
+    let mut bindings = InputBindings::default();
+    let conflicts = bindings.rebind(
+        Action::UseTool(Tool::Custom(0)),
+        Binding::new(InputButton::Key(KeyCode::X)),
+    );
+    assert_eq!(conflicts, vec![Action::Erase]);
+    assert_eq!(bindings.conflicts().len(), 1);
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::utils::HashMap;
+use bevy_app::grid_plugin::{
+    chunk_hexes, chunk_of, ChunkMesh, ChunkMeshBuilder, HexFace, HexHit, Tile, TileEvent,
+};
+use bevy_app::TileVariant;
+use common::headless_app;
+use hexx::{shapes, Hex, HexLayout, HexOrientation};
+
+const TOP_VERTICES: usize = 7;
+const SIDE_VERTICES: usize = 4;
+const TILE_VERTICES: usize = TOP_VERTICES + 6 * SIDE_VERTICES;
+
+fn layout() -> HexLayout {
+    HexLayout {
+        orientation: HexOrientation::Pointy,
+        ..default()
+    }
+}
+
+fn board(columns: &[(Hex, u32)]) -> HashMap<Hex, Vec<Tile>> {
+    let mut board: HashMap<Hex, Vec<Tile>> = shapes::hexagon(Hex::ZERO, 2)
+        .map(|hex| (hex, Vec::new()))
+        .collect();
+    for &(hex, height) in columns {
+        let tile = Tile {
+            entity: Entity::PLACEHOLDER,
+            variant: TileVariant::Cyan,
+        };
+        board.insert(hex, vec![tile; height as usize]);
+    }
+    board
+}
+
+fn tile_vertices(columns: &HashMap<Hex, Vec<Tile>>) -> usize {
+    let layout = layout();
+    let builder = ChunkMeshBuilder {
+        layout: &layout,
+        columns,
+        layer_height: 0.5,
+        margin: 0.05,
+    };
+    builder
+        .build(IVec2::ZERO, 16)
+        .iter()
+        .chain(builder.build(IVec2::NEG_ONE, 16).iter())
+        .chain(builder.build(IVec2::new(-1, 0), 16).iter())
+        .chain(builder.build(IVec2::new(0, -1), 16).iter())
+        .filter(|(variant, _)| variant.is_some())
+        .map(|(_, mesh)| mesh.count_vertices())
+        .sum()
+}
+
+#[test]
+fn chunks_partition_axial_coordinates() {
+    assert_eq!(chunk_of(Hex::new(0, 0), 8), IVec2::new(0, 0));
+    assert_eq!(chunk_of(Hex::new(7, 7), 8), IVec2::new(0, 0));
+    assert_eq!(chunk_of(Hex::new(8, -1), 8), IVec2::new(1, -1));
+    assert_eq!(chunk_of(Hex::new(-8, -9), 8), IVec2::new(-1, -2));
+    assert!(chunk_hexes(IVec2::new(-1, 2), 4).all(|hex| chunk_of(hex, 4) == IVec2::new(-1, 2)));
+    assert_eq!(chunk_hexes(IVec2::ZERO, 4).count(), 16);
+}
+
+#[test]
+fn lone_tile_keeps_every_face() {
+    assert_eq!(tile_vertices(&board(&[(Hex::ZERO, 1)])), TILE_VERTICES);
+}
+
+#[test]
+fn stacked_tiles_hide_the_covered_top() {
+    assert_eq!(
+        tile_vertices(&board(&[(Hex::ZERO, 2)])),
+        2 * TILE_VERTICES - TOP_VERTICES
+    );
+}
+
+#[test]
+fn adjacent_tiles_hide_shared_sides() {
+    let columns = board(&[(Hex::ZERO, 1), (Hex::new(1, 0), 1)]);
+
+    assert_eq!(
+        tile_vertices(&columns),
+        2 * TILE_VERTICES - 2 * SIDE_VERTICES
+    );
+}
+
+#[test]
+fn neighbors_of_equal_height_hide_every_shared_side() {
+    let columns = board(&[(Hex::ZERO, 2), (Hex::new(1, 0), 2)]);
+
+    assert_eq!(
+        tile_vertices(&columns),
+        4 * TILE_VERTICES - 2 * TOP_VERTICES - 4 * SIDE_VERTICES
+    );
+}
+
+#[test]
+fn lower_neighbor_keeps_the_sides_it_falls_short_of() {
+    // The neighbor's top tile stops a margin below the layer above it, so no side is shared.
+    let columns = board(&[(Hex::ZERO, 2), (Hex::new(1, 0), 1)]);
+
+    assert_eq!(tile_vertices(&columns), 3 * TILE_VERTICES - TOP_VERTICES);
+}
+
+#[test]
+fn covered_tops_show_where_the_tile_above_narrows() {
+    // Layer 0 is shared and reaches the hex outline; layer 1 is not and keeps its margin, so
+    // both layer 0 tops are needed to close the step between them.
+    let columns = board(&[(Hex::ZERO, 2), (Hex::new(1, 0), 3)]);
+
+    assert_eq!(
+        tile_vertices(&columns),
+        5 * TILE_VERTICES - TOP_VERTICES - 2 * SIDE_VERTICES
+    );
+}
+
+/// Vertices of the tile meshes lying on the edge between the center hex and its `(1, 0)`
+/// neighbor.
+fn vertices_on_shared_edge(columns: &HashMap<Hex, Vec<Tile>>) -> usize {
+    let layout = layout();
+    let builder = ChunkMeshBuilder {
+        layout: &layout,
+        columns,
+        layer_height: 0.5,
+        margin: 0.05,
+    };
+    let meshes = builder.build(IVec2::ZERO, 16);
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        meshes[&Some(TileVariant::Cyan)].attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("tile mesh has no positions");
+    };
+    let [a, b] = [Hex::ZERO, Hex::new(1, 0)].map(|hex| layout.hex_to_world_pos(hex));
+    let (middle, normal) = ((a + b) / 2.0, (b - a).normalize());
+    positions
+        .iter()
+        .filter(|[x, _, z]| (Vec2::new(*x, *z) - middle).dot(normal).abs() < 1e-4)
+        .count()
+}
+
+#[test]
+fn shared_sides_reach_the_hex_outline() {
+    // Apart, both tiles keep their margin; together they close the slit between them.
+    assert_eq!(
+        vertices_on_shared_edge(&board(&[(Hex::ZERO, 1), (Hex::new(2, 0), 1)])),
+        0
+    );
+    assert!(vertices_on_shared_edge(&board(&[(Hex::ZERO, 1), (Hex::new(1, 0), 1)])) > 0);
+}
+
+#[test]
+fn placing_tiles_builds_one_mesh_per_chunk_and_variant() {
+    let mut app = headless_app();
+    let far = Hex::new(-4, 0);
+    for hex in [Hex::ZERO, Hex::new(1, 0), far] {
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        app.world.send_event(TileEvent::Down(HexHit {
+            hex,
+            layer: 0,
+            face: HexFace::Top,
+            position: Vec3::ZERO,
+        }));
+        app.update();
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .release(MouseButton::Left);
+        app.update();
+    }
+
+    let mut query = app.world.query::<&ChunkMesh>();
+    let mut tile_chunks: Vec<IVec2> = query
+        .iter(&app.world)
+        .filter(|mesh| mesh.variant == Some(TileVariant::Purple))
+        .map(|mesh| mesh.chunk)
+        .collect();
+    tile_chunks.sort_by_key(|chunk| (chunk.x, chunk.y));
+    assert_eq!(tile_chunks, vec![chunk_of(far, 8), chunk_of(Hex::ZERO, 8)]);
+}
@@ -0,0 +1,60 @@
+// Every test file compiles its own copy of this module and uses only some of the helpers.
+#![allow(dead_code)]
+
+use bevy::app::PluginGroupBuilder;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_app::grid_plugin::{HexFace, HexHit, TileEvent};
+use bevy_app::{ready_app, GridTracker, HeadlessPlugins, TileVariant};
+use hexx::Hex;
+
+/// A headless app that has finished loading and set up the grid.
+pub fn headless_app() -> App {
+    ready_app(HeadlessPlugins)
+}
+
+pub fn headless_app_with(plugins: PluginGroupBuilder) -> App {
+    ready_app(plugins)
+}
+
+/// A hit on the ground of `hex`.
+pub fn hit(hex: Hex) -> HexHit {
+    HexHit {
+        hex,
+        layer: 0,
+        face: HexFace::Top,
+        position: Vec3::ZERO,
+    }
+}
+
+pub fn send(app: &mut App, event: TileEvent) {
+    app.world.send_event(event);
+    app.update();
+}
+
+/// Presses and releases the pointer on `hex`, with whatever tool is active.
+pub fn click(app: &mut App, hex: Hex) {
+    send(app, TileEvent::Down(hit(hex)));
+    send(app, TileEvent::Up(hit(hex)));
+}
+
+/// Clicks `hex` with `variant` selected.
+pub fn place(app: &mut App, hex: Hex, variant: TileVariant) {
+    app.world.resource_mut::<GridTracker>().current_tile_variant = variant;
+    click(app, hex);
+}
+
+pub fn key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+    app.world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key_code),
+        state,
+    });
+    app.update();
+}
+
+pub fn tap(app: &mut App, key_code: KeyCode) {
+    key(app, key_code, ButtonState::Pressed);
+    key(app, key_code, ButtonState::Released);
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use bevy_app::{GridConfig, GridTracker};
+use common::{click, headless_app};
+use hexx::Hex;
+
+#[test]
+fn bounds_cover_columns_and_their_tiles() {
+    let mut app = headless_app();
+    let (low, high) = (Hex::new(-1, 0), Hex::new(2, -1));
+    click(&mut app, low);
+    for _ in 0..3 {
+        click(&mut app, high);
+    }
+
+    let layer_height = app.world.resource::<GridConfig>().layer_height;
+    let tracker = app.world.resource::<GridTracker>();
+    let (min, max) = tracker.bounds([low, high], layer_height).unwrap();
+
+    let radius = tracker.layout.hex_size.max_element();
+    for hex in [low, high] {
+        let center = tracker.layout.hex_to_world_pos(hex);
+        assert!(min.x <= center.x - radius + 1e-4 && max.x >= center.x + radius - 1e-4);
+        assert!(min.z <= center.y - radius + 1e-4 && max.z >= center.y + radius - 1e-4);
+    }
+    assert_eq!(min.y, 0.0);
+    assert_eq!(max.y, 3.0 * layer_height);
+}
+
+#[test]
+fn bounds_skip_hexes_off_the_board() {
+    let app = headless_app();
+    let tracker = app.world.resource::<GridTracker>();
+
+    assert_eq!(tracker.bounds([Hex::new(10, 0)], 0.5), None);
+    assert_eq!(
+        tracker.bounds([Hex::ZERO, Hex::new(10, 0)], 0.5),
+        tracker.bounds([Hex::ZERO], 0.5)
+    );
+}
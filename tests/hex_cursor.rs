@@ -0,0 +1,83 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_app::grid_plugin::{HexCursor, HoveredHex, TileEvent};
+use bevy_app::{GridTracker, TileVariant};
+use common::{headless_app, tap};
+use hexx::Hex;
+
+fn cursor(app: &App) -> Hex {
+    app.world.resource::<HexCursor>().hex.unwrap()
+}
+
+#[test]
+fn direction_keys_step_to_the_neighbor_on_that_side() {
+    let mut app = headless_app();
+
+    // Without a camera the screen's right is the world's +x.
+    tap(&mut app, KeyCode::D);
+    let tracker = app.world.resource::<GridTracker>();
+    let east = tracker.layout.hex_to_world_pos(cursor(&app));
+    assert!(east.x > 0.0 && east.y.abs() < 1e-4);
+    assert_eq!(
+        app.world.resource::<HoveredHex>().0.map(|hit| hit.hex),
+        Some(cursor(&app))
+    );
+
+    tap(&mut app, KeyCode::A);
+    assert_eq!(cursor(&app), Hex::ZERO);
+}
+
+#[test]
+fn cursor_stops_at_the_board_edge() {
+    let mut app = headless_app();
+
+    for _ in 0..6 {
+        tap(&mut app, KeyCode::D);
+    }
+    // Straight right of the center is a corner of the board; no neighbor lies further right.
+    let corner = Hex::new(4, 0);
+    assert_eq!(cursor(&app), corner);
+
+    tap(&mut app, KeyCode::D);
+    assert_eq!(cursor(&app), corner);
+}
+
+#[test]
+fn place_and_erase_keys_act_on_the_cursor() {
+    let mut app = headless_app();
+    tap(&mut app, KeyCode::Q);
+    let hex = cursor(&app);
+
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(app.world.resource::<GridTracker>().height(hex), Some(2));
+
+    tap(&mut app, KeyCode::X);
+    assert_eq!(app.world.resource::<GridTracker>().height(hex), Some(1));
+    // Erasing is not a pointer gesture, so tools don't see a long press.
+    let events = app.world.resource::<Events<TileEvent>>();
+    assert!(!events
+        .get_reader()
+        .iter(events)
+        .any(|event| matches!(event, TileEvent::LongPress(_))));
+}
+
+#[test]
+fn variant_keys_cycle_through_the_palette() {
+    let mut app = headless_app();
+    let variant = |app: &App| {
+        app.world
+            .resource::<GridTracker>()
+            .current_tile_variant
+            .clone()
+    };
+    assert_eq!(variant(&app), TileVariant::Purple);
+
+    tap(&mut app, KeyCode::Tab);
+    assert_eq!(variant(&app), TileVariant::Orange);
+    tap(&mut app, KeyCode::Tab);
+    assert_eq!(variant(&app), TileVariant::Cyan);
+    tap(&mut app, KeyCode::Grave);
+    assert_eq!(variant(&app), TileVariant::Orange);
+}
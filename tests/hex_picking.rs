@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_app::grid_plugin::{cast_ray, HexFace, Tile};
+use hexx::{shapes, Hex, HexLayout, HexOrientation};
+
+const LAYER_HEIGHT: f32 = 0.5;
+
+fn layout() -> HexLayout {
+    HexLayout {
+        orientation: HexOrientation::Pointy,
+        ..default()
+    }
+}
+
+fn board(columns: &[(Hex, u32)]) -> HashMap<Hex, Vec<Tile>> {
+    let mut board: HashMap<Hex, Vec<Tile>> = shapes::hexagon(Hex::ZERO, 3)
+        .map(|hex| (hex, Vec::new()))
+        .collect();
+    for &(hex, height) in columns {
+        let tile = Tile {
+            entity: Entity::PLACEHOLDER,
+            variant: default(),
+        };
+        board.insert(hex, vec![tile; height as usize]);
+    }
+    board
+}
+
+fn above(layout: &HexLayout, hex: Hex) -> Ray {
+    let position = layout.hex_to_world_pos(hex);
+    Ray {
+        origin: Vec3::new(position.x, 10.0, position.y),
+        direction: Vec3::NEG_Y,
+    }
+}
+
+/// A ray travelling towards negative x at a fixed height along the row of `Hex::new(n, 0)`.
+fn along_row(height: f32) -> Ray {
+    Ray {
+        origin: Vec3::new(20.0, height, 0.0),
+        direction: Vec3::NEG_X,
+    }
+}
+
+#[test]
+fn straight_down_on_empty_hex_hits_ground() {
+    let layout = layout();
+    let hex = Hex::new(1, -2);
+
+    let hit = cast_ray(above(&layout, hex), &layout, &board(&[]), LAYER_HEIGHT).unwrap();
+
+    assert_eq!(hit.hex, hex);
+    assert_eq!(hit.face, HexFace::Ground);
+    assert_eq!(hit.layer, 0);
+    assert!(hit.position.y.abs() < 1e-4);
+}
+
+#[test]
+fn straight_down_on_column_hits_top_tile() {
+    let layout = layout();
+    let hex = Hex::new(-1, 2);
+
+    let hit = cast_ray(
+        above(&layout, hex),
+        &layout,
+        &board(&[(hex, 3)]),
+        LAYER_HEIGHT,
+    )
+    .unwrap();
+
+    assert_eq!(hit.hex, hex);
+    assert_eq!(hit.face, HexFace::Top);
+    assert_eq!(hit.layer, 2);
+    assert!((hit.position.y - 1.5).abs() < 1e-4);
+}
+
+#[test]
+fn sideways_ray_hits_first_column_side() {
+    let layout = layout();
+    let near = Hex::new(2, 0);
+    let far = Hex::new(0, 0);
+
+    let hit = cast_ray(
+        along_row(0.25),
+        &layout,
+        &board(&[(near, 1), (far, 4)]),
+        LAYER_HEIGHT,
+    )
+    .unwrap();
+
+    assert_eq!(hit.hex, near);
+    assert_eq!(hit.layer, 0);
+    assert_eq!(
+        hit.face,
+        HexFace::Side(near.neighbor_direction(Hex::new(3, 0)).unwrap())
+    );
+}
+
+#[test]
+fn ray_passes_over_lower_columns() {
+    let layout = layout();
+    let near = Hex::new(2, 0);
+    let far = Hex::new(0, 0);
+
+    let hit = cast_ray(
+        along_row(0.75),
+        &layout,
+        &board(&[(near, 1), (far, 2)]),
+        LAYER_HEIGHT,
+    )
+    .unwrap();
+
+    assert_eq!(hit.hex, far);
+    assert_eq!(hit.layer, 1);
+}
+
+#[test]
+fn slanted_ray_lands_on_ground_past_columns() {
+    let layout = layout();
+    let target = layout.hex_to_world_pos(Hex::new(-2, 1));
+    let origin = Vec3::new(target.x + 6.0, 6.0, target.y + 3.0);
+    let ray = Ray {
+        origin,
+        direction: (Vec3::new(target.x, 0.0, target.y) - origin).normalize(),
+    };
+
+    let hit = cast_ray(ray, &layout, &board(&[]), LAYER_HEIGHT).unwrap();
+
+    assert_eq!(hit.hex, Hex::new(-2, 1));
+    assert_eq!(hit.face, HexFace::Ground);
+}
+
+#[test]
+fn ray_outside_board_misses() {
+    let layout = layout();
+
+    assert!(cast_ray(
+        above(&layout, Hex::new(8, 0)),
+        &layout,
+        &board(&[]),
+        LAYER_HEIGHT
+    )
+    .is_none());
+    let upwards = Ray {
+        origin: Vec3::new(0.0, 1.0, 0.0),
+        direction: Vec3::Y,
+    };
+    assert!(cast_ray(upwards, &layout, &board(&[]), LAYER_HEIGHT).is_none());
+}
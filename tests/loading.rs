@@ -0,0 +1,49 @@
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_app::loading_plugin::MANIFEST;
+use bevy_app::{GameState, LoadingPlugin, LoadingProgress};
+use common::headless_app;
+
+#[test]
+fn loading_progress_counts_the_manifest_and_its_files() {
+    let app = headless_app();
+    let progress = app.world.resource::<LoadingProgress>();
+    // The manifest itself, one font and six tool icons.
+    assert_eq!(progress.total, 8);
+    assert!(progress.loaded >= 1);
+    assert!(progress.failed.is_empty());
+    assert!(progress.fraction() > 0.0 && progress.fraction() <= 1.0);
+}
+
+#[test]
+fn a_missing_manifest_stops_loading_and_says_why() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin {
+            asset_folder: "tests/missing_assets".to_string(),
+            ..default()
+        })
+        .add_plugin(LoadingPlugin);
+    for _ in 0..500 {
+        app.update();
+        if !app.world.resource::<LoadingProgress>().failed.is_empty() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(2));
+    }
+
+    let progress = app.world.resource::<LoadingProgress>();
+    assert_eq!(progress.failed, vec![MANIFEST.to_string()]);
+    assert_eq!(progress.loaded, 0);
+    assert!(progress
+        .status()
+        .starts_with(&format!("Could not load:\n{MANIFEST}\n")));
+    assert_eq!(
+        app.world.resource::<State<GameState>>().0,
+        GameState::Loading
+    );
+}
@@ -0,0 +1,336 @@
+mod common;
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy_app::grid_plugin::{GridPlugin, Palette};
+use bevy_app::{
+    CameraBookmark, CameraBookmarks, GameState, GridConfig, GridTracker, HeadlessPlugins, LoadMap,
+    MapFile, MapLoadError, MapLoaded, NewMap, RecentMaps, SaveMap, TileVariant,
+};
+use common::{headless_app, headless_app_with, place};
+use hexx::Hex;
+
+fn variants(app: &App, hex: Hex) -> Vec<TileVariant> {
+    app.world.resource::<GridTracker>().columns[&hex]
+        .iter()
+        .map(|tile| tile.variant.clone())
+        .collect()
+}
+
+fn temp_map(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hex_tiles_{}_{name}.ron", std::process::id()))
+}
+
+#[test]
+fn saved_map_loads_back() {
+    let mut app = headless_app();
+    let path = temp_map("round_trip");
+    place(&mut app, Hex::ZERO, TileVariant::Cyan);
+    place(&mut app, Hex::ZERO, TileVariant::Orange);
+    place(&mut app, Hex::new(1, -1), TileVariant::Purple);
+    app.world.send_event(SaveMap { path: path.clone() });
+    app.update();
+
+    place(&mut app, Hex::new(2, 0), TileVariant::Cyan);
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+
+    assert_eq!(
+        variants(&app, Hex::ZERO),
+        vec![TileVariant::Cyan, TileVariant::Orange]
+    );
+    assert_eq!(variants(&app, Hex::new(1, -1)), vec![TileVariant::Purple]);
+    assert!(variants(&app, Hex::new(2, 0)).is_empty());
+    let mut query = app.world.query_filtered::<(), With<TileVariant>>();
+    assert_eq!(query.iter(&app.world).count(), 3);
+    let tracker = app.world.resource::<GridTracker>();
+    assert_eq!(tracker.tiles.len(), 3);
+    assert_eq!(tracker.columns.len(), 61);
+
+    let events = app.world.resource::<Events<MapLoaded>>();
+    let loaded: Vec<_> = events.get_reader().iter(events).cloned().collect();
+    assert_eq!(loaded.last().unwrap().tile_count, 3);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn camera_bookmarks_are_saved_with_the_map() {
+    let mut app = headless_app();
+    let path = temp_map("bookmarks");
+    let bookmark = CameraBookmark {
+        focus: Vec3::new(1.0, 0.5, -2.0),
+        yaw: 1.0,
+        pitch: 0.6,
+        zoom: 4.0,
+    };
+    let mut bookmarks = CameraBookmarks::default();
+    bookmarks.0.insert(3, bookmark.clone());
+    app.insert_resource(bookmarks);
+    app.world.send_event(SaveMap { path: path.clone() });
+    app.update();
+
+    let map = MapFile::from_ron(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(map.camera_bookmarks.get(&3), Some(&bookmark));
+
+    app.world.resource_mut::<CameraBookmarks>().0.clear();
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+    assert_eq!(
+        app.world.resource::<CameraBookmarks>().0.get(&3),
+        Some(&bookmark)
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn malformed_map_leaves_the_board_alone() {
+    let mut app = headless_app();
+    let path = temp_map("malformed");
+    std::fs::write(&path, "(columns: [oops").unwrap();
+    place(&mut app, Hex::ZERO, TileVariant::Cyan);
+
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+
+    assert_eq!(variants(&app, Hex::ZERO), vec![TileVariant::Cyan]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn maps_listing_a_hex_twice_are_refused() {
+    let mut app = headless_app();
+    let path = temp_map("duplicate");
+    std::fs::write(
+        &path,
+        "(columns: [(hex: (0, 0), tiles: [Cyan]), (hex: (0, 0), tiles: [Orange, Orange])])",
+    )
+    .unwrap();
+    place(&mut app, Hex::ZERO, TileVariant::Purple);
+
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+
+    assert_eq!(variants(&app, Hex::ZERO), vec![TileVariant::Purple]);
+    assert_eq!(app.world.resource::<GridTracker>().tiles.len(), 1);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn custom_colors_are_saved_with_the_palette() {
+    let mut app = headless_app();
+    let path = temp_map("custom_colors");
+    let teal = TileVariant::custom(Color::rgb_u8(10, 200, 180));
+    let unused = TileVariant::Custom { r: 1, g: 2, b: 3 };
+    {
+        let mut palette = app.world.resource_mut::<Palette>();
+        assert!(palette.add(teal.clone()));
+        assert!(palette.add(unused.clone()));
+    }
+    place(&mut app, Hex::ZERO, teal.clone());
+    assert!(app
+        .world
+        .resource::<GridTracker>()
+        .tile_materials
+        .contains_key(&teal));
+    app.world.send_event(SaveMap { path: path.clone() });
+    app.update();
+
+    app.insert_resource(Palette::default());
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+
+    assert_eq!(variants(&app, Hex::ZERO), vec![teal.clone()]);
+    let mut expected = Palette::default();
+    expected.0.extend([teal, unused]);
+    assert_eq!(*app.world.resource::<Palette>(), expected);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn maps_without_a_palette_list_the_colors_they_use() {
+    let mut app = headless_app();
+    let path = temp_map("no_palette");
+    std::fs::write(
+        &path,
+        "(columns: [(hex: (0, 0), tiles: [Custom(r: 255, g: 0, b: 0), Cyan])])",
+    )
+    .unwrap();
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+
+    let mut expected = Palette::default();
+    expected.0.push(TileVariant::Custom { r: 255, g: 0, b: 0 });
+    assert_eq!(*app.world.resource::<Palette>(), expected);
+
+    let mut palette = app.world.resource_mut::<Palette>();
+    for blue in 0..Palette::CAPACITY as u8 {
+        palette.add(TileVariant::Custom {
+            r: 0,
+            g: 0,
+            b: blue,
+        });
+    }
+    assert_eq!(palette.0.len(), Palette::CAPACITY);
+    assert!(!palette.add(TileVariant::Custom { r: 9, g: 9, b: 9 }));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn loaded_palettes_drop_repeats_and_overflow() {
+    let mut app = headless_app();
+    let path = temp_map("long_palette");
+    let colors: Vec<String> = (0..Palette::CAPACITY as u8 + 3)
+        .map(|blue| format!("Custom(r: 0, g: 0, b: {blue})"))
+        .collect();
+    std::fs::write(
+        &path,
+        format!(
+            "(columns: [], palette: [Cyan, Cyan, {}])",
+            colors.join(", ")
+        ),
+    )
+    .unwrap();
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+
+    let palette = app.world.resource::<Palette>();
+    assert_eq!(palette.0.len(), Palette::CAPACITY);
+    assert_eq!(palette.0[0], TileVariant::Cyan);
+    assert_eq!(palette.0[1], TileVariant::Custom { r: 0, g: 0, b: 0 });
+    assert!(!palette.0[1..].contains(&TileVariant::Cyan));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn new_map_clears_the_board_and_palette() {
+    let mut app = headless_app();
+    let teal = TileVariant::Custom {
+        r: 0,
+        g: 128,
+        b: 128,
+    };
+    app.world.resource_mut::<Palette>().add(teal.clone());
+    place(&mut app, Hex::ZERO, teal);
+
+    app.world.send_event(NewMap);
+    app.update();
+
+    assert!(variants(&app, Hex::ZERO).is_empty());
+    assert!(app.world.resource::<GridTracker>().tiles.is_empty());
+    assert_eq!(app.world.resource::<GridTracker>().columns.len(), 61);
+    assert_eq!(*app.world.resource::<Palette>(), Palette::default());
+    let current = &app.world.resource::<GridTracker>().current_tile_variant;
+    assert!(Palette::default().0.contains(current));
+}
+
+#[test]
+fn recent_maps_list_the_latest_first() {
+    let mut app = headless_app();
+    let first = temp_map("recent_first");
+    let second = temp_map("recent_second");
+    app.world.send_event(SaveMap {
+        path: first.clone(),
+    });
+    app.update();
+    app.world.send_event(SaveMap {
+        path: second.clone(),
+    });
+    app.update();
+    app.world.send_event(LoadMap {
+        path: first.clone(),
+    });
+    app.update();
+    app.world.send_event(LoadMap {
+        path: temp_map("recent_missing"),
+    });
+    app.update();
+
+    assert_eq!(
+        app.world.resource::<RecentMaps>().0,
+        vec![first.clone(), second.clone()]
+    );
+    let mut recent = RecentMaps::default();
+    for index in 0..=RecentMaps::LIMIT {
+        recent.push(PathBuf::from(format!("{index}.ron")));
+    }
+    assert_eq!(recent.0.len(), RecentMaps::LIMIT);
+    assert_eq!(
+        recent.0[0],
+        PathBuf::from(format!("{}.ron", RecentMaps::LIMIT))
+    );
+    std::fs::remove_file(first).unwrap();
+    std::fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn the_board_is_not_edited_while_paused() {
+    let mut app = headless_app();
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Paused);
+    app.update();
+
+    place(&mut app, Hex::ZERO, TileVariant::Cyan);
+    assert!(variants(&app, Hex::ZERO).is_empty());
+
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    place(&mut app, Hex::ZERO, TileVariant::Cyan);
+    assert_eq!(variants(&app, Hex::ZERO), vec![TileVariant::Cyan]);
+}
+
+#[test]
+fn maps_opened_from_the_main_menu_start_editing_only_once_loaded() {
+    let mut app = headless_app();
+    let path = temp_map("from_menu");
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::MainMenu);
+    app.update();
+
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world.resource::<State<GameState>>().0,
+        GameState::MainMenu
+    );
+    assert!(app.world.resource::<MapLoadError>().0.is_some());
+
+    std::fs::write(&path, MapFile::empty(1).to_ron().unwrap()).unwrap();
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world.resource::<State<GameState>>().0,
+        GameState::Playing
+    );
+    assert_eq!(*app.world.resource::<MapLoadError>(), MapLoadError(None));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn loaded_maps_grow_an_unbounded_board() {
+    let mut app = headless_app_with(HeadlessPlugins.set(GridPlugin {
+        config: GridConfig {
+            unbounded: true,
+            ..default()
+        },
+    }));
+    let path = temp_map("unbounded");
+    std::fs::write(&path, "(columns: [(hex: (9, 0), tiles: [Cyan])])").unwrap();
+
+    app.world.send_event(LoadMap { path: path.clone() });
+    app.update();
+    app.update();
+
+    let tracker = app.world.resource::<GridTracker>();
+    assert_eq!(tracker.height(Hex::new(10, 0)), Some(0));
+    assert_eq!(tracker.height(Hex::new(8, 1)), Some(0));
+    assert_eq!(tracker.height(Hex::new(11, 0)), None);
+    assert_eq!(tracker.columns.len(), 7);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_app::ui_plugin::MinimapCanvas;
+use bevy_app::{GridTracker, TileVariant};
+use common::{headless_app, place};
+use hexx::Hex;
+
+/// The pixel drawn at the center of `hex`.
+fn pixel_of(canvas: &MinimapCanvas, tracker: &GridTracker, hex: Hex) -> [u8; 4] {
+    let pixel = canvas.world_to_pixel(tracker.layout.hex_to_world_pos(hex));
+    canvas.pixel(pixel.x as u32, pixel.y as u32)
+}
+
+#[test]
+fn columns_are_drawn_in_their_top_color_and_lighter_when_taller() {
+    let mut app = headless_app();
+    place(
+        &mut app,
+        Hex::ZERO,
+        TileVariant::Custom { r: 200, g: 0, b: 0 },
+    );
+    place(
+        &mut app,
+        Hex::new(2, 0),
+        TileVariant::Custom { r: 200, g: 0, b: 0 },
+    );
+    place(
+        &mut app,
+        Hex::new(2, 0),
+        TileVariant::Custom { r: 200, g: 0, b: 0 },
+    );
+    let tracker = app.world.resource::<GridTracker>();
+    let mut canvas = MinimapCanvas::new(64);
+    canvas.redraw(tracker);
+
+    let low = pixel_of(&canvas, tracker, Hex::ZERO);
+    let high = pixel_of(&canvas, tracker, Hex::new(2, 0));
+    let empty = pixel_of(&canvas, tracker, Hex::new(-2, 0));
+    assert!(low[0] > 0 && low[1] == 0 && low[3] == 255);
+    assert!(high[0] > low[0]);
+    assert_eq!(empty[3], 255);
+    assert_ne!(empty, low);
+    // Corners of the canvas lie off the hexagonal board.
+    assert_eq!(canvas.pixel(0, 0), [0; 4]);
+}
+
+#[test]
+fn edits_redraw_only_their_columns() {
+    let mut app = headless_app();
+    let mut canvas = MinimapCanvas::new(64);
+    canvas.redraw(app.world.resource::<GridTracker>());
+    let before = canvas.compose(None);
+
+    place(&mut app, Hex::new(1, 0), TileVariant::Orange);
+    let tracker = app.world.resource::<GridTracker>();
+    assert!(!canvas.needs_refit(tracker));
+    canvas.draw_hexes(tracker, [Hex::new(1, 0)]);
+
+    let mut redrawn = MinimapCanvas::new(64);
+    redrawn.redraw(tracker);
+    assert_eq!(canvas.compose(None), redrawn.compose(None));
+    assert_ne!(canvas.compose(None), before);
+}
+
+#[test]
+fn pixels_map_back_to_the_ground_and_the_footprint_is_outlined() {
+    let app = headless_app();
+    let tracker = app.world.resource::<GridTracker>();
+    let mut canvas = MinimapCanvas::new(64);
+    canvas.redraw(tracker);
+
+    let ground = Vec2::new(1.5, -2.0);
+    let back = canvas.pixel_to_world(canvas.world_to_pixel(ground));
+    assert!((back - ground).length() < 1e-4);
+
+    let footprint = [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(1.0, -1.0),
+        Vec2::new(1.0, 1.0),
+        Vec2::new(-1.0, 1.0),
+    ];
+    let composed = canvas.compose(Some(footprint));
+    let corner = canvas.world_to_pixel(footprint[0]);
+    let index = (corner.y as usize * 64 + corner.x as usize) * 4;
+    assert_eq!(composed[index..index + 4], [255; 4]);
+    let center = canvas.world_to_pixel(Vec2::ZERO);
+    let index = (center.y as usize * 64 + center.x as usize) * 4;
+    assert_ne!(composed[index..index + 4], [255; 4]);
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::window::FileDragAndDrop;
+use bevy_app::grid_plugin::Palette;
+use bevy_app::palette_file::parse_palette;
+use bevy_app::{GridTracker, ImportPalette, PaletteError, PaletteFormat, TileVariant};
+use common::headless_app;
+
+fn rgb(r: u8, g: u8, b: u8) -> TileVariant {
+    TileVariant::Custom { r, g, b }
+}
+
+fn temp_palette(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hex_tiles_{}_{name}", std::process::id()))
+}
+
+/// An `.ase` file with a group around the given color entries.
+fn ase(entries: &[(&[u8; 4], &[f32])]) -> Vec<u8> {
+    let mut bytes = b"ASEF".to_vec();
+    bytes.extend([0, 1, 0, 0]);
+    bytes.extend((entries.len() as u32 + 2).to_be_bytes());
+    bytes.extend([0xc0, 0x01, 0, 0, 0, 0]);
+    for (model, values) in entries {
+        let mut block = Vec::new();
+        block.extend(2u16.to_be_bytes());
+        block.extend([0, b'a', 0, 0]);
+        block.extend(*model);
+        for value in *values {
+            block.extend(value.to_be_bytes());
+        }
+        block.extend(2u16.to_be_bytes());
+        bytes.extend([0, 1]);
+        bytes.extend((block.len() as u32).to_be_bytes());
+        bytes.extend(block);
+    }
+    bytes.extend([0xc0, 0x02, 0, 0, 0, 0]);
+    bytes
+}
+
+#[test]
+fn text_formats_become_custom_variants() {
+    let gpl =
+        "GIMP Palette\nName: Test\nColumns: 2\n# comment\n255   0   0\tRed\n  0 128 255 Sky\n";
+    assert_eq!(
+        parse_palette(PaletteFormat::Gpl, gpl.as_bytes()),
+        Ok(Palette(vec![rgb(255, 0, 0), rgb(0, 128, 255)]))
+    );
+    let paint_net = "; Paint.NET palette\nFF102030\n80A0B0C0\n";
+    assert_eq!(
+        parse_palette(PaletteFormat::PaintNet, paint_net.as_bytes()),
+        Ok(Palette(vec![rgb(16, 32, 48), rgb(160, 176, 192)]))
+    );
+    let hex = "ff0000\n#00FF00\n\nff0000\n";
+    assert_eq!(
+        parse_palette(PaletteFormat::Hex, hex.as_bytes()),
+        Ok(Palette(vec![rgb(255, 0, 0), rgb(0, 255, 0)]))
+    );
+}
+
+#[test]
+fn swatch_exchange_files_convert_every_color_model() {
+    let bytes = ase(&[
+        (b"RGB ", &[1.0, 0.0, 0.0]),
+        (b"CMYK", &[0.0, 1.0, 1.0, 0.5]),
+        (b"Gray", &[0.0]),
+    ]);
+    assert_eq!(
+        parse_palette(PaletteFormat::Ase, &bytes),
+        Ok(Palette(vec![rgb(255, 0, 0), rgb(128, 0, 0), rgb(0, 0, 0)]))
+    );
+
+    let lab = ase(&[(b"LAB ", &[50.0, 0.0, 0.0])]);
+    assert_eq!(
+        parse_palette(PaletteFormat::Ase, &lab),
+        Err(PaletteError::Ase(
+            "unsupported color model `LAB`".to_string()
+        ))
+    );
+    let truncated = &bytes[..bytes.len() - 10];
+    assert_eq!(
+        parse_palette(PaletteFormat::Ase, truncated),
+        Err(PaletteError::Ase("file ends early".to_string()))
+    );
+}
+
+#[test]
+fn malformed_files_report_where_they_go_wrong() {
+    let error = parse_palette(PaletteFormat::Gpl, b"GIMP Palette\n1 2 3\n4 5\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 3: expected red, green and blue from 0 to 255"
+    );
+    let error = parse_palette(PaletteFormat::Gpl, b"Palette\n1 2 3\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 1: expected a `GIMP Palette` header"
+    );
+    let error = parse_palette(PaletteFormat::Hex, b"ff0000\nnot a color\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 2: expected six hex digits, RRGGBB");
+    assert_eq!(
+        parse_palette(PaletteFormat::PaintNet, b"; nothing\n"),
+        Err(PaletteError::Empty)
+    );
+    let error = parse_palette(PaletteFormat::PaintNet, b"shopping list\nff0000ff\n").unwrap_err();
+    assert_eq!(error.to_string(), "unrecognized palette format");
+    let error = parse_palette(PaletteFormat::PaintNet, b"00ff0000\n").unwrap_err();
+    assert_eq!(error, PaletteError::UnrecognizedFormat);
+
+    let too_many: String = (0..=Palette::CAPACITY)
+        .map(|index| format!("{index:06x}\n"))
+        .collect();
+    assert_eq!(
+        parse_palette(PaletteFormat::Hex, too_many.as_bytes()),
+        Err(PaletteError::TooManyColors {
+            count: Palette::CAPACITY + 1
+        })
+    );
+}
+
+#[test]
+fn imported_palette_replaces_the_current_one() {
+    let mut app = headless_app();
+    let path = temp_palette("import.hex");
+    std::fs::write(&path, "112233\n445566\n").unwrap();
+    app.world.send_event(ImportPalette { path: path.clone() });
+    app.update();
+
+    assert_eq!(
+        *app.world.resource::<Palette>(),
+        Palette(vec![rgb(0x11, 0x22, 0x33), rgb(0x44, 0x55, 0x66)])
+    );
+    assert_eq!(
+        app.world.resource::<GridTracker>().current_tile_variant,
+        rgb(0x11, 0x22, 0x33)
+    );
+
+    // A broken file leaves the palette as it was.
+    std::fs::write(&path, "112233\nzz\n").unwrap();
+    app.world.send_event(ImportPalette { path: path.clone() });
+    app.update();
+    assert_eq!(app.world.resource::<Palette>().0.len(), 2);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn dropped_palette_files_are_imported() {
+    let mut app = headless_app();
+    let path = temp_palette("dropped.gpl");
+    std::fs::write(&path, "GIMP Palette\n1 2 3\n").unwrap();
+    app.world.send_event(FileDragAndDrop::DroppedFile {
+        window: Entity::PLACEHOLDER,
+        path_buf: path.clone(),
+    });
+    app.update();
+
+    assert_eq!(app.world.resource::<Palette>().0, vec![rgb(1, 2, 3)]);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use bevy_app::ui_plugin::status_line;
+use bevy_app::{GridTracker, TileVariant};
+use common::{headless_app, place};
+use hexx::Hex;
+
+#[test]
+fn hovered_hex_is_described_in_every_coordinate_system() {
+    let mut app = headless_app();
+    let hex = Hex::new(2, -1);
+    place(&mut app, hex, TileVariant::Cyan);
+    place(
+        &mut app,
+        hex,
+        TileVariant::Custom {
+            r: 255,
+            g: 0,
+            b: 16,
+        },
+    );
+    let tracker = app.world.resource::<GridTracker>();
+
+    assert_eq!(
+        status_line(Some(hex), tracker, "build"),
+        "axial (2, -1)   cube (2, -1, -1)   offset (1, -1)   height 2   top #ff0010   |   \
+         2 tiles   |   build"
+    );
+    assert_eq!(
+        status_line(Some(Hex::ZERO), tracker, "erase"),
+        "axial (0, 0)   cube (0, 0, 0)   offset (0, 0)   height 0   top empty   |   \
+         2 tiles   |   erase"
+    );
+    assert_eq!(
+        status_line(None, tracker, "build"),
+        "no hex   |   2 tiles   |   build"
+    );
+}
@@ -0,0 +1,243 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_app::grid_plugin::TileEvent;
+use bevy_app::{
+    GridConfig, GridPlugin, GridTracker, HeadlessPlugins, TilePlaced, TileRemoved, TileVariant,
+};
+use common::{click, headless_app, headless_app_with, hit, send};
+use hexx::Hex;
+
+fn press(app: &mut App, hex: Hex) {
+    send(app, TileEvent::Down(hit(hex)));
+}
+
+fn hover(app: &mut App, hex: Hex) {
+    send(app, TileEvent::Over(hit(hex)));
+}
+
+fn release(app: &mut App, hex: Hex) {
+    send(app, TileEvent::Up(hit(hex)));
+}
+
+/// Layers of the placed tiles in a column, bottom first.
+fn column(app: &mut App, hex: Hex) -> Vec<u32> {
+    let mut query = app.world.query_filtered::<Entity, With<TileVariant>>();
+    let tracker = app.world.resource::<GridTracker>();
+    let mut layers: Vec<u32> = query
+        .iter(&app.world)
+        .map(|entity| &tracker.tiles[&entity])
+        .filter(|coords| coords.hex == hex)
+        .map(|coords| coords.layer)
+        .collect();
+    layers.sort();
+    layers
+}
+
+fn placed_count(app: &mut App) -> usize {
+    let mut query = app.world.query_filtered::<(), With<TileVariant>>();
+    query.iter(&app.world).count()
+}
+
+#[test]
+fn single_click_places_one_tile() {
+    let mut app = headless_app();
+    let hex = Hex::new(1, 0);
+
+    click(&mut app, hex);
+
+    assert_eq!(column(&mut app, hex), vec![0]);
+    assert_eq!(placed_count(&mut app), 1);
+    assert_eq!(app.world.resource::<GridTracker>().height(hex), Some(1));
+}
+
+#[test]
+fn repeated_clicks_stack_tiles() {
+    let mut app = headless_app();
+    let hex = Hex::ZERO;
+
+    click(&mut app, hex);
+    click(&mut app, hex);
+    click(&mut app, hex);
+
+    assert_eq!(column(&mut app, hex), vec![0, 1, 2]);
+}
+
+#[test]
+fn placement_sends_tile_placed() {
+    let mut app = headless_app();
+    let hex = Hex::new(0, 1);
+
+    click(&mut app, hex);
+
+    let events = app.world.resource::<Events<TilePlaced>>();
+    let placed: Vec<_> = events.get_reader().iter(events).cloned().collect();
+    assert_eq!(placed.len(), 1);
+    assert_eq!(placed[0].hex, hex);
+    assert_eq!(placed[0].layer, 0);
+    assert_eq!(placed[0].variant, TileVariant::Purple);
+}
+
+#[test]
+fn drag_places_on_every_hovered_hex() {
+    let mut app = headless_app();
+    let path = [
+        Hex::new(0, 0),
+        Hex::new(1, 0),
+        Hex::new(2, 0),
+        Hex::new(2, 1),
+    ];
+
+    press(&mut app, path[0]);
+    for hex in &path[1..] {
+        hover(&mut app, *hex);
+    }
+    release(&mut app, path[3]);
+
+    for hex in path {
+        assert_eq!(column(&mut app, hex), vec![0]);
+    }
+    assert_eq!(placed_count(&mut app), path.len());
+}
+
+#[test]
+fn hover_without_button_does_not_place() {
+    let mut app = headless_app();
+
+    hover(&mut app, Hex::new(1, 1));
+
+    assert_eq!(placed_count(&mut app), 0);
+}
+
+#[test]
+fn drag_does_not_climb_above_drag_layer() {
+    let mut app = headless_app();
+    let low = Hex::new(0, 0);
+    let high = Hex::new(1, 0);
+    let beyond = Hex::new(2, 0);
+    click(&mut app, high);
+    click(&mut app, high);
+
+    press(&mut app, low);
+    hover(&mut app, high);
+    hover(&mut app, beyond);
+    release(&mut app, beyond);
+
+    assert_eq!(column(&mut app, low), vec![0]);
+    assert_eq!(column(&mut app, high), vec![0, 1]);
+    assert_eq!(column(&mut app, beyond), vec![0]);
+}
+
+#[test]
+fn drag_from_higher_column_fills_lower_ones() {
+    let mut app = headless_app();
+    let high = Hex::new(0, 0);
+    let low = Hex::new(1, 0);
+    click(&mut app, high);
+
+    press(&mut app, high);
+    hover(&mut app, low);
+    release(&mut app, low);
+
+    assert_eq!(column(&mut app, high), vec![0, 1]);
+    assert_eq!(column(&mut app, low), vec![0]);
+}
+
+#[test]
+fn drag_ignores_repeated_hover_of_last_hex() {
+    let mut app = headless_app();
+    let start = Hex::new(0, 0);
+    let next = Hex::new(1, 0);
+    click(&mut app, start);
+
+    // Dragging from a column of height one allows up to layer one elsewhere, so only the
+    // `last_hex` check keeps the second hover from stacking another tile.
+    press(&mut app, start);
+    hover(&mut app, next);
+    hover(&mut app, next);
+    release(&mut app, next);
+
+    assert_eq!(column(&mut app, start), vec![0, 1]);
+    assert_eq!(column(&mut app, next), vec![0]);
+}
+
+#[test]
+fn drag_ends_on_up() {
+    let mut app = headless_app();
+    let start = Hex::new(0, 0);
+    let after = Hex::new(1, 0);
+
+    click(&mut app, start);
+    hover(&mut app, after);
+
+    assert!(column(&mut app, after).is_empty());
+}
+
+#[test]
+fn long_press_removes_the_top_tile() {
+    let mut app = headless_app();
+    let hex = Hex::new(-1, 1);
+    click(&mut app, hex);
+    click(&mut app, hex);
+    let top = app.world.resource::<GridTracker>().columns[&hex][1].entity;
+
+    app.world.send_event(TileEvent::LongPress(hit(hex)));
+    app.update();
+
+    assert_eq!(column(&mut app, hex), vec![0]);
+    assert!(app.world.get_entity(top).is_none());
+    assert!(!app.world.resource::<GridTracker>().tiles.contains_key(&top));
+    let events = app.world.resource::<Events<TileRemoved>>();
+    let removed: Vec<_> = events.get_reader().iter(events).cloned().collect();
+    assert_eq!(removed.len(), 1);
+    assert_eq!((removed[0].hex, removed[0].layer), (hex, 1));
+}
+
+#[test]
+fn long_press_on_an_empty_column_does_nothing() {
+    let mut app = headless_app();
+
+    app.world.send_event(TileEvent::LongPress(hit(Hex::ZERO)));
+    app.update();
+
+    assert_eq!(
+        app.world.resource::<GridTracker>().height(Hex::ZERO),
+        Some(0)
+    );
+}
+
+#[test]
+fn bounded_board_ignores_hexes_past_the_edge() {
+    let mut app = headless_app();
+    let edge = Hex::new(4, 0);
+    let outside = Hex::new(5, 0);
+
+    click(&mut app, edge);
+    click(&mut app, outside);
+
+    assert_eq!(column(&mut app, edge), vec![0]);
+    assert!(column(&mut app, outside).is_empty());
+    assert_eq!(app.world.resource::<GridTracker>().height(outside), None);
+}
+
+#[test]
+fn unbounded_board_grows_around_placed_tiles() {
+    let mut app = headless_app_with(HeadlessPlugins.set(GridPlugin {
+        config: GridConfig {
+            unbounded: true,
+            ..default()
+        },
+    }));
+    let mut hex = Hex::new(4, 0);
+
+    for _ in 0..3 {
+        click(&mut app, hex);
+        hex = Hex::new(hex.x + 1, 0);
+    }
+
+    assert_eq!(column(&mut app, Hex::new(6, 0)), vec![0]);
+    let tracker = app.world.resource::<GridTracker>();
+    assert_eq!(tracker.height(Hex::new(7, 0)), Some(0));
+    assert_eq!(tracker.height(Hex::new(8, 0)), None);
+    assert_eq!(tracker.columns.len(), 61 + 3 * 3);
+}
@@ -0,0 +1,360 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_app::grid_plugin::{
+    ActiveTool, Edit, EditHistory, EditorTool, EditorToolAppExt, EditorTools, HexHit, MapAccess,
+    Selection, TileEvent, Tool,
+};
+use bevy_app::{GridPlugin, GridTracker, HeadlessPlugins, TileVariant};
+use common::{click, headless_app, headless_app_with, hit, key, send};
+use hexx::Hex;
+
+fn drag(app: &mut App, path: &[Hex]) {
+    send(app, TileEvent::Down(hit(path[0])));
+    for hex in &path[1..] {
+        send(app, TileEvent::Over(hit(*hex)));
+    }
+    send(app, TileEvent::Up(hit(path[path.len() - 1])));
+}
+
+fn use_tool(app: &mut App, tool: Tool) {
+    app.world.resource_mut::<ActiveTool>().set(tool);
+}
+
+fn select_variant(app: &mut App, variant: TileVariant) {
+    app.world.resource_mut::<GridTracker>().current_tile_variant = variant;
+}
+
+fn tops(app: &App, hexes: &[Hex]) -> Vec<Option<TileVariant>> {
+    let tracker = app.world.resource::<GridTracker>();
+    hexes
+        .iter()
+        .map(|hex| tracker.columns[hex].last().map(|tile| tile.variant.clone()))
+        .collect()
+}
+
+fn heights(app: &App, hexes: &[Hex]) -> Vec<u32> {
+    let tracker = app.world.resource::<GridTracker>();
+    hexes
+        .iter()
+        .map(|hex| tracker.height(*hex).unwrap())
+        .collect()
+}
+
+#[test]
+fn hotkeys_switch_tools() {
+    let mut app = headless_app();
+    assert_eq!(app.world.resource::<ActiveTool>().tool, Tool::Build);
+
+    key(&mut app, KeyCode::T, ButtonState::Pressed);
+
+    assert_eq!(app.world.resource::<ActiveTool>().tool, Tool::Paint);
+}
+
+#[test]
+fn erase_drag_clears_down_to_the_first_height() {
+    let mut app = headless_app();
+    let path = [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)];
+    click(&mut app, path[0]);
+    click(&mut app, path[1]);
+    click(&mut app, path[1]);
+
+    use_tool(&mut app, Tool::Erase);
+    drag(&mut app, &path);
+
+    assert_eq!(heights(&app, &path), vec![0, 1, 0]);
+}
+
+#[test]
+fn paint_recolors_top_tiles_along_the_drag() {
+    let mut app = headless_app();
+    let path = [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)];
+    click(&mut app, path[0]);
+    click(&mut app, path[0]);
+    click(&mut app, path[1]);
+
+    use_tool(&mut app, Tool::Paint);
+    select_variant(&mut app, TileVariant::Cyan);
+    drag(&mut app, &path);
+
+    assert_eq!(
+        tops(&app, &path),
+        vec![Some(TileVariant::Cyan), Some(TileVariant::Cyan), None]
+    );
+    assert_eq!(
+        app.world.resource::<GridTracker>().columns[&path[0]][0].variant,
+        TileVariant::Purple
+    );
+    assert_eq!(
+        app.world.resource::<EditHistory>().steps.last(),
+        Some(&vec![Edit::Recolored {
+            hex: path[1],
+            layer: 0,
+            previous_variant: TileVariant::Purple,
+        }])
+    );
+}
+
+#[test]
+fn edits_of_different_kinds_in_one_frame_undo_in_order() {
+    let mut app = headless_app();
+    let hex = Hex::ZERO;
+    select_variant(&mut app, TileVariant::Cyan);
+    click(&mut app, hex);
+
+    // The paint tool recolors the tile, then a long press in the same frame removes it.
+    use_tool(&mut app, Tool::Paint);
+    select_variant(&mut app, TileVariant::Orange);
+    app.world.send_event(TileEvent::Down(hit(hex)));
+    app.world.send_event(TileEvent::LongPress(hit(hex)));
+    app.update();
+    send(&mut app, TileEvent::Up(hit(hex)));
+    assert_eq!(
+        app.world.resource::<EditHistory>().steps.last(),
+        Some(&vec![
+            Edit::Recolored {
+                hex,
+                layer: 0,
+                previous_variant: TileVariant::Cyan,
+            },
+            Edit::Removed {
+                hex,
+                variant: TileVariant::Orange,
+            },
+        ])
+    );
+
+    key(&mut app, KeyCode::LControl, ButtonState::Pressed);
+    key(&mut app, KeyCode::Z, ButtonState::Pressed);
+    assert_eq!(tops(&app, &[hex]), vec![Some(TileVariant::Cyan)]);
+    assert_eq!(heights(&app, &[hex]), vec![1]);
+}
+
+#[test]
+fn fill_recolors_the_connected_area_and_undoes_in_one_step() {
+    let mut app = headless_app();
+    let area = [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)];
+    let other_variant = Hex::new(0, 1);
+    let other_height = Hex::new(-1, 0);
+    for hex in area {
+        click(&mut app, hex);
+    }
+    click(&mut app, other_height);
+    click(&mut app, other_height);
+    select_variant(&mut app, TileVariant::Orange);
+    click(&mut app, other_variant);
+
+    use_tool(&mut app, Tool::Fill);
+    select_variant(&mut app, TileVariant::Cyan);
+    let steps = app.world.resource::<EditHistory>().steps.len();
+    click(&mut app, area[0]);
+
+    assert_eq!(tops(&app, &area), vec![Some(TileVariant::Cyan); 3]);
+    assert_eq!(
+        tops(&app, &[other_variant, other_height]),
+        vec![Some(TileVariant::Orange), Some(TileVariant::Purple)]
+    );
+    assert_eq!(app.world.resource::<EditHistory>().steps.len(), steps + 1);
+
+    key(&mut app, KeyCode::LControl, ButtonState::Pressed);
+    key(&mut app, KeyCode::Z, ButtonState::Pressed);
+    assert_eq!(tops(&app, &area), vec![Some(TileVariant::Purple); 3]);
+}
+
+#[test]
+fn fill_on_empty_ground_covers_the_connected_empty_hexes() {
+    let mut app = headless_app();
+    use_tool(&mut app, Tool::Fill);
+    click(&mut app, Hex::ZERO);
+
+    let tracker = app.world.resource::<GridTracker>();
+    assert!(tracker.columns.values().all(|column| column.len() == 1));
+}
+
+#[test]
+fn select_drag_replaces_the_selection() {
+    let mut app = headless_app();
+    use_tool(&mut app, Tool::Select);
+    click(&mut app, Hex::new(3, 0));
+    drag(&mut app, &[Hex::new(0, 0), Hex::new(1, 0)]);
+
+    let selection = &app.world.resource::<Selection>().0;
+    assert_eq!(selection.len(), 2);
+    assert!(selection.contains(&Hex::new(0, 0)) && selection.contains(&Hex::new(1, 0)));
+    assert_eq!(heights(&app, &[Hex::new(0, 0), Hex::new(1, 0)]), vec![0, 0]);
+}
+
+#[test]
+fn eyedropper_picks_a_variant_and_returns_to_the_previous_tool() {
+    let mut app = headless_app();
+    select_variant(&mut app, TileVariant::Orange);
+    click(&mut app, Hex::ZERO);
+    select_variant(&mut app, TileVariant::Cyan);
+
+    use_tool(&mut app, Tool::Paint);
+    use_tool(&mut app, Tool::Eyedropper);
+    click(&mut app, Hex::ZERO);
+
+    assert_eq!(
+        app.world.resource::<GridTracker>().current_tile_variant,
+        TileVariant::Orange
+    );
+    assert_eq!(app.world.resource::<ActiveTool>().tool, Tool::Paint);
+}
+
+/// Records the callbacks it gets and raises pressed columns by two tiles.
+struct Raise(Arc<Mutex<Vec<String>>>);
+
+impl EditorTool for Raise {
+    fn name(&self) -> &str {
+        "Raise"
+    }
+
+    fn hover(&mut self, hit: &HexHit, _map: &mut MapAccess) {
+        self.0.lock().unwrap().push(format!("hover {:?}", hit.hex));
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        self.0.lock().unwrap().push(format!("press {:?}", hit.hex));
+        map.place(hit.hex, TileVariant::Orange);
+        map.place(hit.hex, TileVariant::Orange);
+    }
+
+    fn drag(&mut self, hit: &HexHit, _map: &mut MapAccess) {
+        self.0.lock().unwrap().push(format!("drag {:?}", hit.hex));
+    }
+
+    fn release(&mut self, hit: &HexHit, _map: &mut MapAccess) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("release {:?}", hit.hex));
+    }
+}
+
+#[test]
+fn custom_tools_get_pointer_callbacks_and_edit_the_map() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut app = headless_app_with(
+        HeadlessPlugins
+            .build()
+            .add_before::<GridPlugin, _>(RegisterRaise(calls.clone())),
+    );
+    let tool = app.world.resource::<EditorTools>().find("Raise").unwrap();
+    assert_eq!(tool, Tool::Custom(0));
+    let order: Vec<Tool> = app
+        .world
+        .resource::<EditorTools>()
+        .iter()
+        .map(|(tool, _)| tool)
+        .collect();
+    assert_eq!(order[..6], Tool::BUILT_IN);
+
+    use_tool(&mut app, tool);
+    send(&mut app, TileEvent::Over(hit(Hex::ZERO)));
+    drag(&mut app, &[Hex::ZERO, Hex::new(1, 0)]);
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "hover Hex { x: 0, y: 0 }",
+            "press Hex { x: 0, y: 0 }",
+            "drag Hex { x: 1, y: 0 }",
+            "release Hex { x: 1, y: 0 }",
+        ]
+    );
+    assert_eq!(heights(&app, &[Hex::ZERO, Hex::new(1, 0)]), vec![2, 0]);
+
+    // Edits made through `MapAccess` can be undone like any other.
+    key(&mut app, KeyCode::LControl, ButtonState::Pressed);
+    key(&mut app, KeyCode::Z, ButtonState::Pressed);
+    assert_eq!(heights(&app, &[Hex::ZERO]), vec![0]);
+}
+
+/// Adds `Raise` before the grid plugin is built.
+struct RegisterRaise(Arc<Mutex<Vec<String>>>);
+
+impl Plugin for RegisterRaise {
+    fn build(&self, app: &mut App) {
+        app.add_editor_tool(Raise(self.0.clone()));
+    }
+}
+
+#[test]
+#[should_panic(expected = "at most 256 custom tools")]
+fn custom_tool_numbers_do_not_wrap() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut tools = EditorTools::default();
+    for number in 0..=255 {
+        assert_eq!(tools.register(Raise(calls.clone())), Tool::Custom(number));
+    }
+    tools.register(Raise(calls));
+}
+
+/// Swaps the top tile for the current variant by taking it off and placing a new one.
+struct Replace;
+
+impl EditorTool for Replace {
+    fn name(&self) -> &str {
+        "Replace"
+    }
+
+    fn press(&mut self, hit: &HexHit, map: &mut MapAccess) {
+        let variant = map.current_variant().clone();
+        if map.remove_top(hit.hex) {
+            map.place(hit.hex, variant);
+        }
+    }
+}
+
+#[test]
+fn custom_tools_can_mix_edits_and_undo_them_in_order() {
+    let mut app = headless_app();
+    click(&mut app, Hex::ZERO);
+    select_variant(&mut app, TileVariant::Cyan);
+    click(&mut app, Hex::ZERO);
+    let tool = app.world.resource_mut::<EditorTools>().register(Replace);
+
+    use_tool(&mut app, tool);
+    select_variant(&mut app, TileVariant::Orange);
+    click(&mut app, Hex::ZERO);
+    assert_eq!(
+        app.world.resource::<GridTracker>().columns[&Hex::ZERO]
+            .iter()
+            .map(|tile| tile.variant.clone())
+            .collect::<Vec<_>>(),
+        vec![TileVariant::Purple, TileVariant::Orange]
+    );
+
+    key(&mut app, KeyCode::LControl, ButtonState::Pressed);
+    key(&mut app, KeyCode::Z, ButtonState::Pressed);
+    assert_eq!(heights(&app, &[Hex::ZERO]), vec![2]);
+    assert_eq!(tops(&app, &[Hex::ZERO]), vec![Some(TileVariant::Cyan)]);
+}
+
+#[derive(Resource, Default)]
+struct ToolChanges(usize);
+
+fn count_tool_changes(active: Res<ActiveTool>, mut changes: ResMut<ToolChanges>) {
+    if active.is_changed() {
+        changes.0 += 1;
+    }
+}
+
+#[test]
+fn hovering_leaves_the_active_tool_unchanged() {
+    let mut app = headless_app();
+    app.init_resource::<ToolChanges>()
+        .add_system(count_tool_changes);
+    app.update();
+    let changes = app.world.resource::<ToolChanges>().0;
+
+    for hex in [Hex::ZERO, Hex::new(1, 0), Hex::new(2, 0)] {
+        send(&mut app, TileEvent::Over(hit(hex)));
+    }
+
+    assert_eq!(app.world.resource::<ToolChanges>().0, changes);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+use bevy::render::camera::camera_system;
+use bevy::window::{PrimaryWindow, WindowCreated, WindowResized};
+use bevy_app::GridTracker;
+use common::headless_app;
+use hexx::Hex;
+
+/// A headless app with a window and a camera looking straight down on the board, so touches
+/// can be picked.
+fn touch_app() -> App {
+    let mut app = headless_app();
+    app.add_event::<WindowCreated>()
+        .add_event::<WindowResized>()
+        .add_system(camera_system::<Projection>);
+    app.world.spawn((Window::default(), PrimaryWindow));
+    let transform = Transform::from_xyz(0.0, 20.0, 0.0).looking_at(Vec3::ZERO, Vec3::NEG_Z);
+    app.world.spawn((
+        Camera::default(),
+        Projection::default(),
+        GlobalTransform::from(transform),
+    ));
+    app.update();
+    app
+}
+
+fn touch(app: &mut App, phase: TouchPhase, position: Vec2) {
+    app.world.send_event(TouchInput {
+        phase,
+        position,
+        force: None,
+        id: 0,
+    });
+    app.update();
+}
+
+fn tap_center(app: &mut App) {
+    let window = app
+        .world
+        .query_filtered::<&Window, With<PrimaryWindow>>()
+        .single(&app.world);
+    let center = Vec2::new(window.width(), window.height()) / 2.0;
+    touch(app, TouchPhase::Started, center);
+    touch(app, TouchPhase::Ended, center);
+}
+
+fn height(app: &App, hex: Hex) -> usize {
+    app.world.resource::<GridTracker>().columns[&hex].len()
+}
+
+#[test]
+fn tap_places_a_tile() {
+    let mut app = touch_app();
+    tap_center(&mut app);
+    assert_eq!(height(&app, Hex::ZERO), 1);
+}
+
+#[test]
+fn tap_on_a_button_leaves_the_board_alone() {
+    let mut app = touch_app();
+    // What `ui_focus_system` makes of a button under the finger.
+    app.world.spawn(ButtonBundle {
+        interaction: Interaction::Clicked,
+        ..default()
+    });
+    tap_center(&mut app);
+    assert_eq!(height(&app, Hex::ZERO), 0);
+}